@@ -0,0 +1,71 @@
+-- PostgreSQL schema matching the SQLite migrations up to 009_add_saved_search_destinations.sql
+
+-- Every listing ever found; prices keep the original text next to the parsed amount (in minor units)
+CREATE TABLE IF NOT EXISTS jackets (
+    id TEXT PRIMARY KEY,
+    source TEXT,
+    title TEXT NOT NULL,
+    brand TEXT,
+    price TEXT NOT NULL,
+    price_amount_minor BIGINT,
+    price_currency TEXT,
+    url TEXT NOT NULL,
+    image_url TEXT,
+    discovered_at TIMESTAMPTZ NOT NULL,
+    size TEXT,
+    measurements TEXT,
+    condition TEXT,
+    description TEXT,
+    status TEXT NOT NULL DEFAULT 'available',
+    first_seen TIMESTAMPTZ,
+    last_seen TIMESTAMPTZ,
+    sold_at TIMESTAMPTZ,
+    notified_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_jackets_source_status ON jackets (source, status);
+
+-- Every price observed for a listing, one row per check
+CREATE TABLE IF NOT EXISTS price_history (
+    id BIGSERIAL PRIMARY KEY,
+    jacket_id TEXT NOT NULL REFERENCES jackets (id),
+    price TEXT NOT NULL,
+    price_amount_minor BIGINT,
+    price_currency TEXT,
+    observed_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_history_jacket ON price_history (jacket_id, observed_at);
+
+-- Notifications waiting to be delivered, one row per event and notifier
+CREATE TABLE IF NOT EXISTS notification_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    notifier TEXT NOT NULL,
+    jacket_id TEXT REFERENCES jackets (id),
+    event JSONB NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_outbox_due ON notification_outbox (status, next_attempt_at);
+
+-- Named filters a listing must match to be announced, and where to send their matches
+CREATE TABLE IF NOT EXISTS saved_searches (
+    name TEXT PRIMARY KEY,
+    keywords JSONB NOT NULL DEFAULT '[]',
+    exclude_keywords JSONB NOT NULL DEFAULT '[]',
+    brands JSONB NOT NULL DEFAULT '[]',
+    exclude_brands JSONB NOT NULL DEFAULT '[]',
+    min_price NUMERIC,
+    max_price NUMERIC,
+    currency TEXT,
+    sizes JSONB NOT NULL DEFAULT '[]',
+    sites JSONB NOT NULL DEFAULT '[]',
+    notify JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
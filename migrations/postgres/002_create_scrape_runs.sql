@@ -0,0 +1,23 @@
+-- One row per check
+CREATE TABLE IF NOT EXISTS scrape_runs (
+    id BIGSERIAL PRIMARY KEY,
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL
+);
+
+-- How each scraper's search went during a check
+CREATE TABLE IF NOT EXISTS scrape_run_scrapers (
+    id BIGSERIAL PRIMARY KEY,
+    run_id BIGINT NOT NULL REFERENCES scrape_runs (id),
+    scraper TEXT NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL,
+    pages_fetched INTEGER NOT NULL,
+    items_parsed INTEGER NOT NULL,
+    items_matched INTEGER NOT NULL,
+    new_items INTEGER NOT NULL,
+    error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_scrape_run_scrapers_run ON scrape_run_scrapers (run_id);
+CREATE INDEX IF NOT EXISTS idx_scrape_run_scrapers_scraper ON scrape_run_scrapers (scraper, started_at);
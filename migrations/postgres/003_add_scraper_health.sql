@@ -0,0 +1,6 @@
+-- Counters behind the scraper health checks
+ALTER TABLE scrape_run_scrapers ADD COLUMN IF NOT EXISTS missing_titles INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE scrape_run_scrapers ADD COLUMN IF NOT EXISTS missing_prices INTEGER NOT NULL DEFAULT 0;
+
+-- Why a successful search looked wrong; empty if it looked healthy
+ALTER TABLE scrape_run_scrapers ADD COLUMN IF NOT EXISTS warnings JSONB NOT NULL DEFAULT '[]';
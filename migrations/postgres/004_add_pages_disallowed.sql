@@ -0,0 +1,2 @@
+-- Search result pages a scraper skipped because robots.txt disallows them
+ALTER TABLE scrape_run_scrapers ADD COLUMN IF NOT EXISTS pages_disallowed INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Add detail page fields collected by the enrichment pass
+ALTER TABLE jackets ADD COLUMN size TEXT;
+ALTER TABLE jackets ADD COLUMN measurements TEXT;
+ALTER TABLE jackets ADD COLUMN condition TEXT;
+ALTER TABLE jackets ADD COLUMN description TEXT;
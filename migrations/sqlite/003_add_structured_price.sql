@@ -0,0 +1,4 @@
+-- Store the parsed price amount (in minor units, e.g. cents) and ISO currency
+-- alongside the original price text kept in the `price` column
+ALTER TABLE jackets ADD COLUMN price_amount_minor INTEGER;
+ALTER TABLE jackets ADD COLUMN price_currency TEXT;
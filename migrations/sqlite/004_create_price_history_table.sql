@@ -0,0 +1,15 @@
+-- Every price observed for a listing, one row per check
+CREATE TABLE IF NOT EXISTS price_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    jacket_id TEXT NOT NULL REFERENCES jackets (id),
+    price TEXT NOT NULL,
+    price_amount_minor INTEGER,
+    price_currency TEXT,
+    observed_at DATETIME NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_history_jacket ON price_history (jacket_id, observed_at);
+
+-- Seed the history with the price each listing was discovered at
+INSERT INTO price_history (jacket_id, price, price_amount_minor, price_currency, observed_at)
+SELECT id, price, price_amount_minor, price_currency, discovered_at FROM jackets;
@@ -0,0 +1,10 @@
+-- Track each listing's availability over time
+ALTER TABLE jackets ADD COLUMN source TEXT;
+ALTER TABLE jackets ADD COLUMN status TEXT NOT NULL DEFAULT 'available';
+ALTER TABLE jackets ADD COLUMN first_seen DATETIME;
+ALTER TABLE jackets ADD COLUMN last_seen DATETIME;
+ALTER TABLE jackets ADD COLUMN sold_at DATETIME;
+
+UPDATE jackets SET first_seen = discovered_at, last_seen = discovered_at;
+
+CREATE INDEX IF NOT EXISTS idx_jackets_source_status ON jackets (source, status);
@@ -0,0 +1,5 @@
+-- Remember which listings have been announced, so failed notifications are retried
+ALTER TABLE jackets ADD COLUMN notified_at DATETIME;
+
+-- Listings saved before delivery was tracked were notified when they were discovered
+UPDATE jackets SET notified_at = discovered_at;
@@ -0,0 +1,18 @@
+-- Brand reported by the website, used by saved search brand filters
+ALTER TABLE jackets ADD COLUMN brand TEXT;
+
+-- Named filters a listing must match to be announced; list columns hold JSON arrays
+CREATE TABLE IF NOT EXISTS saved_searches (
+    name TEXT PRIMARY KEY,
+    keywords TEXT NOT NULL DEFAULT '[]',
+    exclude_keywords TEXT NOT NULL DEFAULT '[]',
+    brands TEXT NOT NULL DEFAULT '[]',
+    exclude_brands TEXT NOT NULL DEFAULT '[]',
+    min_price TEXT,
+    max_price TEXT,
+    currency TEXT,
+    sizes TEXT NOT NULL DEFAULT '[]',
+    sites TEXT NOT NULL DEFAULT '[]',
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL
+);
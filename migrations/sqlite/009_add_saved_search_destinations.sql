@@ -0,0 +1,2 @@
+-- Destinations a saved search sends its matches to, as a JSON array; empty uses the default notifiers
+ALTER TABLE saved_searches ADD COLUMN notify TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,6 @@
+-- Counters behind the scraper health checks
+ALTER TABLE scrape_run_scrapers ADD COLUMN missing_titles INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE scrape_run_scrapers ADD COLUMN missing_prices INTEGER NOT NULL DEFAULT 0;
+
+-- Why a successful search looked wrong, as a JSON array; empty if it looked healthy
+ALTER TABLE scrape_run_scrapers ADD COLUMN warnings TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,466 @@
+//! Command-line interface and the commands that run once and exit
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use tracing::warn;
+
+use crate::database::{self, DatabaseConfig, ListingFilter};
+use crate::health;
+use crate::jacket_finder::{JacketFinder, default_scrapers};
+use crate::models::{
+    CheckReport, Jacket, ListingAction, ListingStatus, NotificationEvent, Price, ScrapeStats, ScraperRun,
+};
+use crate::notifiers::configured_notifiers;
+
+/// Find N-1 deck jackets on vintage shops and get notified about new listings
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// What to do; runs the bot when omitted
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Top-level subcommands
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Check every 5 minutes and deliver notifications until stopped
+    Run,
+    /// Run a single check, deliver its notifications and exit (for cron or systemd timers)
+    Once(OnceArgs),
+    /// Show stored listings, most recently discovered first
+    List(ListArgs),
+    /// Scrape one website and print what it finds, without saving or notifying
+    TestScraper(TestScraperArgs),
+    /// Send a sample new listing notification through the configured notifiers
+    TestNotify(TestNotifyArgs),
+    /// Show recent checks and how each scraper's search went
+    Runs(RunsArgs),
+    /// Manage the database
+    #[command(subcommand)]
+    Db(DbCommand),
+}
+
+/// Arguments of the `once` command
+#[derive(Debug, Args)]
+pub struct OnceArgs {
+    /// Only report what would be saved and notified, without writing or sending anything
+    #[arg(long)]
+    dry_run: bool,
+    /// Output format of the dry run report
+    #[arg(long, value_enum, default_value_t = ReportFormat::Table, requires = "dry_run")]
+    format: ReportFormat,
+}
+
+/// How a report is printed
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    /// One line per entry
+    Table,
+    /// The full report as JSON
+    Json,
+}
+
+/// Filters for the `list` command
+#[derive(Debug, Args)]
+pub struct ListArgs {
+    /// Only listings found by this scraper
+    #[arg(long)]
+    source: Option<String>,
+    /// Only listings with this status
+    #[arg(long, value_parser = status_parser())]
+    status: Option<ListingStatus>,
+    /// Only listings whose title contains this text
+    #[arg(long)]
+    title: Option<String>,
+    /// Only listings matching this saved search
+    #[arg(long)]
+    search: Option<String>,
+    /// Maximum number of listings to show
+    #[arg(long, default_value_t = 50)]
+    limit: usize,
+}
+
+/// Arguments of the `test-scraper` command
+#[derive(Debug, Args)]
+pub struct TestScraperArgs {
+    /// Scraper name, e.g. "Marrkt"
+    name: String,
+    /// Also fetch the detail page of every listing found
+    #[arg(long)]
+    details: bool,
+}
+
+/// Arguments of the `test-notify` command
+#[derive(Debug, Args)]
+pub struct TestNotifyArgs {
+    /// Only send through this notifier: discord, telegram or email
+    #[arg(long)]
+    notifier: Option<String>,
+}
+
+/// Filters for the `runs` command
+#[derive(Debug, Args)]
+pub struct RunsArgs {
+    /// Only show this scraper's results
+    #[arg(long)]
+    scraper: Option<String>,
+    /// Number of most recent checks to show
+    #[arg(long, default_value_t = 10)]
+    limit: u32,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+    format: ReportFormat,
+}
+
+/// Database subcommands
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+    /// Create the database if needed and apply pending migrations
+    Migrate,
+    /// Show how many listings, price observations and notifications are stored
+    Stats,
+}
+
+/// Parser accepting listing statuses as stored in the database
+fn status_parser() -> impl TypedValueParser<Value = ListingStatus> {
+    PossibleValuesParser::new(["available", "sold_out", "missing"]).map(|status| ListingStatus::from_db(&status))
+}
+
+/// Run a single check and deliver the notifications it queued, or report what it would do
+///
+/// # Arguments
+/// * `args` - Whether to do a dry run and how to print its report
+///
+/// # Returns
+/// * `Result<()>` - Success or error from scraping, database, or notification operations
+pub async fn once(args: OnceArgs) -> Result<()> {
+    if args.dry_run {
+        let report = JacketFinder::new_read_only().await?.dry_run().await?;
+        match args.format {
+            ReportFormat::Table => print_report(&report),
+            ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        }
+        return Ok(());
+    }
+
+    let finder = JacketFinder::new().await?;
+    finder.check_for_new_jackets().await?;
+
+    // Failed deliveries stay queued and are retried by the next run
+    finder.outbox_worker().drain().await
+}
+
+/// Print stored listings matching the given filters
+///
+/// # Arguments
+/// * `args` - Filters and limit from the command line
+///
+/// # Returns
+/// * `Result<()>` - Success, database error, or an error for an unknown saved search
+pub async fn list(args: ListArgs) -> Result<()> {
+    let database = database::connect_from_env().await?;
+    let filter = ListingFilter {
+        source: args.source,
+        status: args.status,
+        title: args.title,
+    };
+    let mut jackets = database.list_jackets(&filter).await?;
+
+    if let Some(name) = &args.search {
+        let searches = database.get_saved_searches().await?;
+        let Some(search) = searches.iter().find(|search| search.name.eq_ignore_ascii_case(name)) else {
+            bail!("No saved search named '{name}'");
+        };
+        jackets.retain(|jacket| search.matches(jacket));
+    }
+
+    for jacket in jackets.iter().take(args.limit) {
+        print_listing(jacket);
+    }
+    println!("Showing {} of {} listings", jackets.len().min(args.limit), jackets.len());
+    Ok(())
+}
+
+/// Scrape one website and print the listings found
+///
+/// # Arguments
+/// * `args` - Scraper name and whether to fetch detail pages
+///
+/// # Returns
+/// * `Result<()>` - Success, or a configuration or scraping error
+pub async fn test_scraper(args: TestScraperArgs) -> Result<()> {
+    let scrapers = default_scrapers()?;
+    let Some(scraper) = scrapers
+        .iter()
+        .find(|scraper| scraper.config().name.eq_ignore_ascii_case(&args.name))
+    else {
+        let names: Vec<&str> = scrapers.iter().map(|scraper| scraper.config().name.as_str()).collect();
+        bail!("No scraper named '{}' - available scrapers: {}", args.name, names.join(", "));
+    };
+
+    let mut stats = ScrapeStats::default();
+    let result = scraper.search_jackets(&mut stats).await;
+    println!(
+        "Fetched {} pages, parsed {} items, {} matched the filter",
+        stats.pages_fetched, stats.items_parsed, stats.items_matched
+    );
+
+    let mut results = result?;
+    for failure in &results.failures {
+        println!("Failed: {failure}");
+    }
+    for problem in health::assess(&stats, &[]) {
+        println!("Unhealthy: {problem}");
+    }
+    for jacket in &mut results.jackets {
+        if args.details
+            && let Err(e) = scraper.enrich_jacket(jacket, &mut stats).await
+        {
+            warn!("Failed to fetch details for {}: {}", jacket.url, e);
+        }
+        print_listing(jacket);
+    }
+
+    println!("Found {} listings on {}", results.jackets.len(), scraper.config().name);
+    Ok(())
+}
+
+/// Send a sample notification directly, bypassing the outbox
+///
+/// # Arguments
+/// * `args` - Optional notifier to restrict the test to
+///
+/// # Returns
+/// * `Result<()>` - Success, or an error if no notifier matched or any send failed
+pub async fn test_notify(args: TestNotifyArgs) -> Result<()> {
+    let notifiers: Vec<_> = configured_notifiers()?
+        .into_iter()
+        .filter(|notifier| {
+            args.notifier
+                .as_deref()
+                .is_none_or(|name| notifier.name().eq_ignore_ascii_case(name))
+        })
+        .collect();
+
+    if notifiers.is_empty() {
+        match &args.notifier {
+            Some(name) => bail!("Notifier '{name}' is not configured"),
+            None => bail!("No notifiers are configured"),
+        }
+    }
+
+    let event = NotificationEvent::NewListing(sample_jacket());
+    let mut failed = 0;
+    for notifier in &notifiers {
+        match notifier.notify(&event).await {
+            Ok(()) => println!("{}: sent", notifier.name()),
+            Err(e) => {
+                println!("{}: failed: {:#}", notifier.name(), e);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!("{} of {} test notifications failed", failed, notifiers.len());
+    }
+    Ok(())
+}
+
+/// Print recent checks with one line per scraper
+///
+/// # Arguments
+/// * `args` - Scraper filter, number of checks and output format
+///
+/// # Returns
+/// * `Result<()>` - Success or database error
+pub async fn runs(args: RunsArgs) -> Result<()> {
+    let mut runs = database::connect_from_env().await?.recent_scrape_runs(args.limit).await?;
+    if let Some(name) = &args.scraper {
+        for run in &mut runs {
+            run.scrapers.retain(|scraper| scraper.scraper.eq_ignore_ascii_case(name));
+        }
+        runs.retain(|run| !run.scrapers.is_empty());
+    }
+
+    if let ReportFormat::Json = args.format {
+        println!("{}", serde_json::to_string_pretty(&runs)?);
+        return Ok(());
+    }
+
+    for run in &runs {
+        println!(
+            "#{:<6} {}  took {}",
+            run.id,
+            run.started_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            format_duration(run.started_at, run.finished_at)
+        );
+        for scraper in &run.scrapers {
+            print_scraper_run(scraper);
+        }
+    }
+    if runs.is_empty() {
+        println!("No checks recorded yet");
+    }
+    Ok(())
+}
+
+/// Create the database if needed and apply pending migrations
+///
+/// # Returns
+/// * `Result<()>` - Success or connection/migration error
+pub async fn db_migrate() -> Result<()> {
+    let config = DatabaseConfig::from_env()?;
+    database::connect(&config).await?;
+    println!("Database {} is up to date", config.url);
+    Ok(())
+}
+
+/// Print row counts of the database
+///
+/// # Returns
+/// * `Result<()>` - Success or database error
+pub async fn db_stats() -> Result<()> {
+    let stats = database::connect_from_env().await?.stats().await?;
+
+    let total: i64 = stats.listings_by_status.iter().map(|(_, count)| count).sum();
+    println!("Listings: {total}");
+    for (status, count) in &stats.listings_by_status {
+        println!("  {status:<12} {count:>6}");
+    }
+
+    println!("Listings per website:");
+    for (source, count) in &stats.listings_by_source {
+        println!("  {source:<12} {count:>6}");
+    }
+
+    println!("Price observations: {}", stats.price_observations);
+    println!("Saved searches: {}", stats.saved_searches);
+
+    println!("Notification outbox:");
+    for (status, count) in &stats.outbox_by_status {
+        println!("  {status:<12} {count:>6}");
+    }
+
+    println!("Checks recorded: {}", stats.scrape_runs);
+    Ok(())
+}
+
+/// Print one listing as a status/price/source/title line followed by its URL
+fn print_listing(jacket: &Jacket) {
+    println!(
+        "{:<9} {:>14}  {:<14} {}",
+        jacket.status.as_str(),
+        jacket.price.to_string(),
+        jacket.source,
+        jacket.title
+    );
+    println!("{:<9} {}", "", jacket.url);
+}
+
+/// Print a check report as one line per listing and alert, followed by a summary
+fn print_report(report: &CheckReport) {
+    for outcome in &report.listings {
+        let price = match &outcome.action {
+            ListingAction::PriceDrop { old_price, percent } => {
+                format!("{} (was {}, -{}%)", outcome.jacket.price, old_price, percent.round_dp(1))
+            }
+            _ => outcome.jacket.price.to_string(),
+        };
+        println!("{:<14} {}", outcome.action.as_str(), outcome.jacket.title);
+        println!(
+            "{:<14} {} | {} | {} | {}",
+            "", outcome.jacket.status, price, outcome.jacket.source, outcome.jacket.url
+        );
+        if !outcome.recipients.is_empty() {
+            println!("{:<14} notify: {}", "", outcome.recipients.join(", "));
+        } else if outcome.action.is_notable() {
+            println!("{:<14} not announced: no matching saved search or configured notifier", "");
+        }
+    }
+
+    for alert in &report.alerts {
+        match &alert.event {
+            NotificationEvent::Error { context, message } => println!("{:<14} {}: {}", "error", context, message),
+            NotificationEvent::ScraperUnhealthy { scraper, problems } => {
+                println!("{:<14} {}: {}", "unhealthy", scraper, problems.join("; "));
+            }
+            _ => continue,
+        }
+        println!("{:<14} notify: {}", "", alert.recipients.join(", "));
+    }
+
+    for (source, count) in &report.missing {
+        println!("{:<14} {} listings on {} are no longer listed", "missing", count, source);
+    }
+
+    for scraper in &report.scrapers {
+        print_scraper_run(scraper);
+    }
+
+    let verb = if report.dry_run { "would be" } else { "were" };
+    println!(
+        "{} listings found: {} new, {} price drops, {} back in stock; {} notifications {} queued",
+        report.listings.len(),
+        report.announced("new"),
+        report.announced("price_drop"),
+        report.announced("back_in_stock"),
+        report.notification_count(),
+        verb
+    );
+    if report.dry_run {
+        println!("Dry run: nothing was saved or sent");
+    }
+}
+
+/// Print how one scraper's search went, followed by any error of a partly failed search and any health warnings
+fn print_scraper_run(run: &ScraperRun) {
+    let took = format_duration(run.started_at, run.finished_at);
+    let partial = run.stats.pages_fetched > 0;
+    if run.error.is_none() || partial {
+        println!(
+            "  {:<14} {:>3} pages {:>5} parsed {:>4} matched {:>4} new   {}",
+            run.scraper,
+            run.stats.pages_fetched,
+            run.stats.items_parsed,
+            run.stats.items_matched,
+            run.new_items,
+            took
+        );
+    }
+    match &run.error {
+        Some(error) if partial => println!("  {:<14} partly failed: {}", "", error),
+        Some(error) => println!("  {:<14} failed after {}: {}", run.scraper, took, error),
+        None => {}
+    }
+    for warning in &run.warnings {
+        println!("  {:<14} unhealthy: {}", "", warning);
+    }
+}
+
+/// Format the time between two instants as seconds with one decimal, e.g. `2.5s`
+fn format_duration(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
+    format!("{:.1}s", (end - start).to_std().unwrap_or_default().as_secs_f64())
+}
+
+/// Listing used by `test-notify`, clearly marked as a test
+fn sample_jacket() -> Jacket {
+    Jacket {
+        id: "test-notification".to_string(),
+        source: "Test".to_string(),
+        title: "Test notification - N-1 Deck Jacket".to_string(),
+        brand: Some("Buzz Rickson's".to_string()),
+        price: Price::parse("€ 249,00"),
+        url: "https://example.com/products/n-1-deck-jacket".to_string(),
+        image_url: None,
+        discovered_at: Utc::now(),
+        size: Some("40".to_string()),
+        measurements: None,
+        condition: Some("Used - good".to_string()),
+        description: Some("Sample listing sent by `jacket-finder test-notify`".to_string()),
+        status: ListingStatus::Available,
+    }
+}
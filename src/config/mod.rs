@@ -0,0 +1,55 @@
+//! Helpers for reading settings from the environment
+
+use anyhow::{Context, Result};
+use std::str::FromStr;
+
+/// Parse a boolean setting such as `true`, `false`, `1` or `0`
+///
+/// # Arguments
+/// * `value` - Setting value; `yes`/`no` and `on`/`off` are accepted too
+///
+/// # Returns
+/// * `Option<bool>` - The value, or `None` if it is not a boolean
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Read a boolean environment variable
+///
+/// # Arguments
+/// * `name` - Variable name
+/// * `default` - Value used when the variable is unset
+///
+/// # Returns
+/// * `Result<bool>` - The value, or an error naming the variable if it is not a boolean
+pub fn env_bool(name: &str, default: bool) -> Result<bool> {
+    match std::env::var(name) {
+        Ok(value) => parse_bool(&value).with_context(|| format!("Invalid {name} {value:?}, expected \"true\" or \"false\"")),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Read an environment variable parsed with `FromStr`
+///
+/// # Arguments
+/// * `name` - Variable name
+/// * `default` - Value used when the variable is unset
+///
+/// # Returns
+/// * `Result<T>` - The value, or an error naming the variable if it does not parse
+pub fn env_parse<T: FromStr>(name: &str, default: T) -> Result<T>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match std::env::var(name) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid {name}: {value:?}")),
+        Err(_) => Ok(default),
+    }
+}
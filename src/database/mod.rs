@@ -1,87 +1,251 @@
-//! `SQLite` database operations for jacket storage and duplicate prevention
+//! Storage of listings, saved searches and queued notifications, in `SQLite` or `PostgreSQL`
 
-use anyhow::Result;
-use sqlx::{Row, Sqlite, SqlitePool, migrate::MigrateDatabase};
-use std::collections::HashSet;
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteSynchronous;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing::info;
 
-use crate::models::Jacket;
+use crate::config::{env_bool, env_parse};
+use crate::models::{ListingStatus, NotificationEvent, Price, ScrapeRun, ScraperRun};
+use crate::traits::Storage;
 
-/// `SQLite` database connection and operations
-pub struct Database {
-    pool: SqlitePool,
-}
+mod postgres;
+mod sqlite;
 
-impl Database {
-    /// Create new database connection with migrations
-    /// 
-    /// # Returns
-    /// * `Result<Self>` - New Database instance or connection/migration error
-    pub async fn new() -> Result<Self> {
-        let db_url = "sqlite:database/jackets.db";
+use postgres::PostgresDatabase;
+use sqlite::SqliteDatabase;
 
-        if !Sqlite::database_exists(db_url).await.unwrap_or(false) {
-            info!("Creating database file");
-            Sqlite::create_database(db_url).await?;
-        }
+/// Shared handle to whichever storage backend the database URL selects
+pub type Database = Arc<dyn Storage>;
+
+/// Default location of the `SQLite` database, relative to the working directory
+const DEFAULT_DATABASE_URL: &str = "sqlite:database/jackets.db";
+
+/// URL of a private in-memory database
+const IN_MEMORY_DATABASE_URL: &str = "sqlite::memory:";
 
-        let pool = SqlitePool::connect(db_url).await?;
+/// Default maximum number of pooled connections
+const DEFAULT_MAX_CONNECTIONS: u32 = 5;
 
-        info!("Running database migrations");
-        sqlx::migrate!("./migrations").run(&pool).await?;
+/// Default time to wait for another connection's lock before failing, in milliseconds
+const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5000;
 
-        info!("Database initialized successfully");
-        Ok(Self { pool })
+/// Database location and connection settings
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    /// Connection URL, e.g. `sqlite:/var/lib/jacket-finder/jackets.db`, `sqlite::memory:`
+    /// or `postgres://jackets@db.example.com/jackets`
+    pub url: String,
+    /// Maximum number of pooled connections; in-memory databases always use one
+    pub max_connections: u32,
+    /// Whether `SQLite` uses write-ahead logging, so reads do not wait for writes
+    pub wal: bool,
+    /// How long `SQLite` waits for a lock held by another connection before failing
+    pub busy_timeout: Duration,
+    /// How often `SQLite` waits for data to reach the disk; `normal` is safe with WAL
+    pub synchronous: SqliteSynchronous,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: DEFAULT_DATABASE_URL.to_string(),
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            wal: true,
+            busy_timeout: Duration::from_millis(DEFAULT_BUSY_TIMEOUT_MS),
+            synchronous: SqliteSynchronous::Normal,
+        }
     }
+}
 
-    /// Get all existing jacket IDs for duplicate checking
-    /// 
+impl DatabaseConfig {
+    /// Read the database configuration from the environment
+    ///
     /// # Returns
-    /// * `Result<HashSet<String>>` - Set of existing jacket IDs or database error
-    pub async fn get_existing_jacket_ids(&self) -> Result<HashSet<String>> {
-        let rows = sqlx::query("SELECT id FROM jackets")
-            .fetch_all(&self.pool)
-            .await?;
-
-        let ids = rows
-            .into_iter()
-            .map(|row| row.get::<String, _>("id"))
-            .collect();
-
-        Ok(ids)
+    /// * `Result<Self>` - Configuration with defaults for unset variables, or an error for invalid values
+    pub fn from_env() -> Result<Self> {
+        let max_connections = env_parse("DATABASE_MAX_CONNECTIONS", DEFAULT_MAX_CONNECTIONS)?;
+        if max_connections == 0 {
+            bail!("DATABASE_MAX_CONNECTIONS must be at least 1");
+        }
+
+        Ok(Self {
+            url: std::env::var("DATABASE_URL").unwrap_or_else(|_| DEFAULT_DATABASE_URL.to_string()),
+            max_connections,
+            wal: env_bool("DATABASE_WAL", true)?,
+            busy_timeout: Duration::from_millis(env_parse("DATABASE_BUSY_TIMEOUT_MS", DEFAULT_BUSY_TIMEOUT_MS)?),
+            synchronous: match std::env::var("DATABASE_SYNCHRONOUS") {
+                Ok(value) => SqliteSynchronous::from_str(value.trim()).with_context(|| {
+                    format!("Invalid DATABASE_SYNCHRONOUS {value:?}, expected off, normal, full or extra")
+                })?,
+                Err(_) => SqliteSynchronous::Normal,
+            },
+        })
+    }
+
+    /// Whether the URL names an in-memory database, which disappears with its last connection
+    fn is_in_memory(&self) -> bool {
+        self.url.contains(":memory:") || self.url.contains("mode=memory")
     }
 
-    /// Save a new jacket to the database
-    /// 
-    /// # Arguments
-    /// * `jacket` - Reference to the jacket to save
-    /// 
+    /// Determine the storage backend from the URL scheme
+    ///
     /// # Returns
-    /// * `Result<()>` - Success or database insertion error
-    pub async fn save_jacket(&self, jacket: &Jacket) -> Result<()> {
-        sqlx::query(
-            r"
-            INSERT INTO jackets (id, title, price, url, image_url, discovered_at)
-            VALUES (?, ?, ?, ?, ?, ?)
-            ",
-        )
-        .bind(&jacket.id)
-        .bind(&jacket.title)
-        .bind(&jacket.price)
-        .bind(&jacket.url)
-        .bind(&jacket.image_url)
-        .bind(jacket.discovered_at)
-        .execute(&self.pool)
-        .await?;
-
-        Ok(())
+    /// * `Result<Backend>` - The backend, or an error for an unsupported URL
+    fn backend(&self) -> Result<Backend> {
+        // Anything else would be taken for a file name by SQLite
+        if self.url.starts_with("sqlite:") {
+            Ok(Backend::Sqlite)
+        } else if self.url.starts_with("postgres://") || self.url.starts_with("postgresql://") {
+            Ok(Backend::Postgres)
+        } else {
+            bail!(
+                "Invalid DATABASE_URL {:?}, expected a URL such as sqlite:database/jackets.db or postgres://host/jackets",
+                self.url
+            )
+        }
     }
 }
 
-impl Clone for Database {
-    fn clone(&self) -> Self {
-        Self {
-            pool: self.pool.clone(),
+/// Storage backend selected by the database URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    /// A `sqlite:` URL naming a file or an in-memory database
+    Sqlite,
+    /// A `postgres://` or `postgresql://` URL
+    Postgres,
+}
+
+/// Open the database described by the environment, creating and migrating it as needed
+///
+/// # Returns
+/// * `Result<Database>` - Open database or configuration/connection/migration error
+pub async fn connect_from_env() -> Result<Database> {
+    connect(&DatabaseConfig::from_env()?).await
+}
+
+/// Open a database, creating and migrating it as needed
+///
+/// # Arguments
+/// * `config` - Database location and connection settings
+///
+/// # Returns
+/// * `Result<Database>` - Open database or connection/migration error
+pub async fn connect(config: &DatabaseConfig) -> Result<Database> {
+    Ok(match config.backend()? {
+        Backend::Sqlite => Arc::new(SqliteDatabase::new(config).await?),
+        Backend::Postgres => Arc::new(
+            PostgresDatabase::new(config)
+                .await
+                .context("Failed to open the PostgreSQL database")?,
+        ),
+    })
+}
+
+/// Open a database without ever writing to it, for dry runs
+///
+/// A database that does not exist yet is replaced by an empty in-memory one, so every
+/// listing is new.
+///
+/// # Arguments
+/// * `config` - Database location and connection settings
+///
+/// # Returns
+/// * `Result<Database>` - Read-only database, or an error if the database needs migrating
+pub async fn connect_read_only(config: &DatabaseConfig) -> Result<Database> {
+    Ok(match config.backend()? {
+        Backend::Sqlite => Arc::new(SqliteDatabase::open_read_only(config).await?),
+        Backend::Postgres => {
+            let database = PostgresDatabase::open_read_only(config)
+                .await
+                .context("Failed to open the PostgreSQL database")?;
+            if let Some(database) = database {
+                Arc::new(database)
+            } else {
+                info!("The database has no tables yet - using an empty in-memory database");
+                Arc::new(SqliteDatabase::in_memory(config).await?)
+            }
         }
+    })
+}
+
+/// Stored state of a listing that has been seen before
+#[derive(Debug, Clone)]
+pub struct KnownListing {
+    /// Name of the scraper that found the listing
+    pub source: String,
+    /// Current price
+    pub price: Price,
+    /// Status as of the last check
+    pub status: ListingStatus,
+}
+
+/// A notification waiting in the outbox for one notifier
+#[derive(Debug, Clone)]
+pub struct OutboxEntry {
+    pub id: i64,
+    /// Routing key of the notifier that should deliver the event: its name, or a destination key
+    pub notifier: String,
+    pub event: NotificationEvent,
+    /// Number of failed delivery attempts so far
+    pub attempts: u32,
+}
+
+/// Filters for listing stored jackets; unset filters match every listing
+#[derive(Debug, Clone, Default)]
+pub struct ListingFilter {
+    /// Name of the scraper that found the listing
+    pub source: Option<String>,
+    /// Current status of the listing
+    pub status: Option<ListingStatus>,
+    /// Text the title must contain, case-insensitively
+    pub title: Option<String>,
+}
+
+/// Row counts summarizing the database contents
+#[derive(Debug, Clone)]
+pub struct DatabaseStats {
+    /// Number of listings per status
+    pub listings_by_status: Vec<(String, i64)>,
+    /// Number of listings per website
+    pub listings_by_source: Vec<(String, i64)>,
+    /// Number of recorded price observations
+    pub price_observations: i64,
+    /// Number of saved searches
+    pub saved_searches: i64,
+    /// Number of outbox entries per status
+    pub outbox_by_status: Vec<(String, i64)>,
+    /// Number of recorded checks
+    pub scrape_runs: i64,
+}
+
+/// Attach per-scraper results to the checks they belong to
+///
+/// # Arguments
+/// * `runs` - ID, start and end of each check, newest first
+/// * `scrapers` - Per-scraper results with the ID of their check, in insertion order
+///
+/// # Returns
+/// * `Vec<ScrapeRun>` - The checks with their scrapers, newest first
+fn assemble_runs(
+    runs: Vec<(i64, DateTime<Utc>, DateTime<Utc>)>,
+    scrapers: Vec<(i64, ScraperRun)>,
+) -> Vec<ScrapeRun> {
+    let mut by_run: HashMap<i64, Vec<ScraperRun>> = HashMap::new();
+    for (run_id, scraper) in scrapers {
+        by_run.entry(run_id).or_default().push(scraper);
     }
+
+    runs.into_iter()
+        .map(|(id, started_at, finished_at)| ScrapeRun {
+            id,
+            started_at,
+            finished_at,
+            scrapers: by_run.remove(&id).unwrap_or_default(),
+        })
+        .collect()
 }
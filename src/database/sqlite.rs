@@ -0,0 +1,729 @@
+//! `SQLite` storage backend, a single database file shared by the instances on one machine
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
+use std::collections::HashMap;
+use std::str::FromStr;
+use tracing::info;
+
+use super::{
+    DatabaseConfig, DatabaseStats, IN_MEMORY_DATABASE_URL, KnownListing, ListingFilter, OutboxEntry, assemble_runs,
+};
+use crate::models::{Jacket, ListingStatus, NotificationEvent, Price, ScrapeRun, ScrapeStats, ScraperRun};
+use crate::searches::SavedSearch;
+use crate::traits::Storage;
+
+/// `SQLite` database connection and operations
+#[derive(Clone)]
+pub struct SqliteDatabase {
+    pool: SqlitePool,
+}
+
+impl SqliteDatabase {
+    /// Create new database connection with migrations
+    ///
+    /// # Arguments
+    /// * `config` - Database location and connection settings
+    ///
+    /// # Returns
+    /// * `Result<Self>` - New database instance or connection/migration error
+    pub async fn new(config: &DatabaseConfig) -> Result<Self> {
+        let options = connect_options(config, false)?;
+
+        if !config.is_in_memory() {
+            let path = options.clone().get_filename();
+            if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create database directory {}", parent.display()))?;
+            }
+            if !path.exists() {
+                info!("Creating database file {}", path.display());
+            }
+        }
+
+        let pool = pool_options(config).connect_with(options).await?;
+
+        info!("Running database migrations");
+        sqlx::migrate!("./migrations/sqlite").run(&pool).await?;
+
+        let database = Self { pool };
+        database.backfill_structured_prices().await?;
+
+        info!("Database initialized successfully");
+        Ok(database)
+    }
+
+    /// Open the database without ever writing to it, for dry runs
+    ///
+    /// Without a database file, an empty in-memory database stands in, so every listing is new.
+    ///
+    /// # Arguments
+    /// * `config` - Database location and connection settings
+    ///
+    /// # Returns
+    /// * `Result<Self>` - Read-only database instance, or an error if the database needs migrating
+    pub async fn open_read_only(config: &DatabaseConfig) -> Result<Self> {
+        let options = connect_options(config, true)?;
+
+        if config.is_in_memory() || !options.clone().get_filename().exists() {
+            info!("No database file yet - using an empty in-memory database");
+            return Self::in_memory(config).await;
+        }
+
+        let pool = pool_options(config).connect_with(options).await?;
+
+        let applied: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+            .fetch_one(&pool)
+            .await?;
+        let latest = sqlx::migrate!("./migrations/sqlite").iter().map(|migration| migration.version).max();
+        if applied < latest {
+            bail!("The database has pending migrations - run `jacket-finder db migrate` first");
+        }
+
+        Ok(Self { pool })
+    }
+
+    /// Create an empty, fully migrated in-memory database
+    ///
+    /// # Arguments
+    /// * `config` - Connection settings to keep; the URL is replaced
+    ///
+    /// # Returns
+    /// * `Result<Self>` - New database instance or migration error
+    pub async fn in_memory(config: &DatabaseConfig) -> Result<Self> {
+        let in_memory = DatabaseConfig {
+            url: IN_MEMORY_DATABASE_URL.to_string(),
+            ..config.clone()
+        };
+        Self::new(&in_memory).await
+    }
+
+    /// Parse the raw price text of rows saved before prices were structured
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success or database error
+    async fn backfill_structured_prices(&self) -> Result<()> {
+        let rows = sqlx::query(
+            "SELECT id, price FROM jackets WHERE price_amount_minor IS NULL AND price_currency IS NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut updated = 0;
+        for row in rows {
+            let price = Price::parse(&row.get::<String, _>("price"));
+            if price.amount.is_none() && price.currency.is_none() {
+                continue;
+            }
+
+            sqlx::query("UPDATE jackets SET price_amount_minor = ?, price_currency = ? WHERE id = ?")
+                .bind(price.amount_minor())
+                .bind(&price.currency)
+                .bind(row.get::<String, _>("id"))
+                .execute(&self.pool)
+                .await?;
+            updated += 1;
+        }
+
+        if updated > 0 {
+            info!("Parsed structured prices for {} existing jackets", updated);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteDatabase {
+    async fn get_known_listings(&self) -> Result<HashMap<String, KnownListing>> {
+        let rows = sqlx::query("SELECT id, source, price, price_amount_minor, price_currency, status FROM jackets")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let listings = rows
+            .into_iter()
+            .map(|row| {
+                let listing = KnownListing {
+                    source: row.get::<Option<String>, _>("source").unwrap_or_default(),
+                    price: Price::from_parts(
+                        row.get("price"),
+                        row.get("price_amount_minor"),
+                        row.get("price_currency"),
+                    ),
+                    status: ListingStatus::from_db(row.get("status")),
+                };
+                (row.get::<String, _>("id"), listing)
+            })
+            .collect();
+
+        Ok(listings)
+    }
+
+    async fn save_jacket(
+        &self,
+        jacket: &Jacket,
+        event: Option<&NotificationEvent>,
+        notifiers: &[String],
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        let inserted = sqlx::query(
+            r"
+            INSERT INTO jackets (
+                id, source, title, brand, price, price_amount_minor, price_currency, url, image_url,
+                discovered_at, size, measurements, condition, description,
+                status, first_seen, last_seen, sold_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (id) DO NOTHING
+            ",
+        )
+        .bind(&jacket.id)
+        .bind(&jacket.source)
+        .bind(&jacket.title)
+        .bind(&jacket.brand)
+        .bind(&jacket.price.raw)
+        .bind(jacket.price.amount_minor())
+        .bind(&jacket.price.currency)
+        .bind(&jacket.url)
+        .bind(&jacket.image_url)
+        .bind(jacket.discovered_at)
+        .bind(&jacket.size)
+        .bind(&jacket.measurements)
+        .bind(&jacket.condition)
+        .bind(&jacket.description)
+        .bind(jacket.status.as_str())
+        .bind(jacket.discovered_at)
+        .bind(jacket.discovered_at)
+        .bind((jacket.status == ListingStatus::SoldOut).then_some(jacket.discovered_at))
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+
+        if !inserted {
+            return Ok(false);
+        }
+
+        insert_price_history(&mut tx, &jacket.id, &jacket.price, jacket.discovered_at).await?;
+
+        if let Some(event) = event {
+            insert_outbox_entries(&mut tx, event, notifiers, jacket.discovered_at).await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn record_sighting(
+        &self,
+        jacket: &Jacket,
+        event: Option<&NotificationEvent>,
+        notifiers: &[String],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r"
+            UPDATE jackets
+            SET source = ?1,
+                status = ?2,
+                last_seen = ?3,
+                sold_at = CASE
+                    WHEN ?2 = 'available' THEN NULL
+                    WHEN ?2 = 'sold_out' AND status != 'sold_out' THEN ?3
+                    ELSE sold_at
+                END,
+                price = ?4,
+                price_amount_minor = ?5,
+                price_currency = ?6
+            WHERE id = ?7
+            ",
+        )
+        .bind(&jacket.source)
+        .bind(jacket.status.as_str())
+        .bind(jacket.discovered_at)
+        .bind(&jacket.price.raw)
+        .bind(jacket.price.amount_minor())
+        .bind(&jacket.price.currency)
+        .bind(&jacket.id)
+        .execute(&mut *tx)
+        .await?;
+
+        insert_price_history(&mut tx, &jacket.id, &jacket.price, jacket.discovered_at).await?;
+
+        if let Some(event) = event {
+            insert_outbox_entries(&mut tx, event, notifiers, jacket.discovered_at).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn enqueue(&self, event: &NotificationEvent, notifiers: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        insert_outbox_entries(&mut tx, event, notifiers, Utc::now()).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn claim_outbox_entries(&self, now: DateTime<Utc>, lease_until: DateTime<Utc>) -> Result<Vec<OutboxEntry>> {
+        // A single statement holds the write lock throughout, so no other connection claims the same rows
+        let rows = sqlx::query(
+            r"
+            UPDATE notification_outbox
+            SET next_attempt_at = ?1
+            WHERE status = 'pending' AND next_attempt_at <= ?2
+            RETURNING id, notifier, event, attempts
+            ",
+        )
+        .bind(lease_until)
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut entries = rows
+            .into_iter()
+            .map(|row| {
+                let id: i64 = row.get("id");
+                let event = serde_json::from_str(row.get("event"))
+                    .with_context(|| format!("Invalid event in outbox entry {id}"))?;
+
+                Ok(OutboxEntry {
+                    id,
+                    notifier: row.get("notifier"),
+                    event,
+                    attempts: row.get("attempts"),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // RETURNING does not keep any order
+        entries.sort_by_key(|entry| entry.id);
+        Ok(entries)
+    }
+
+    async fn mark_delivered(&self, entry: &OutboxEntry, delivered_at: DateTime<Utc>) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE notification_outbox SET status = 'delivered', delivered_at = ? WHERE id = ?")
+            .bind(delivered_at)
+            .bind(entry.id)
+            .execute(&mut *tx)
+            .await?;
+
+        if let NotificationEvent::NewListing(jacket) = &entry.event {
+            sqlx::query("UPDATE jackets SET notified_at = ? WHERE id = ? AND notified_at IS NULL")
+                .bind(delivered_at)
+                .bind(&jacket.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        entry_id: i64,
+        next_attempt_at: Option<DateTime<Utc>>,
+        error: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r"
+            UPDATE notification_outbox
+            SET attempts = attempts + 1,
+                status = CASE WHEN ?1 IS NULL THEN 'dead' ELSE 'pending' END,
+                next_attempt_at = COALESCE(?1, next_attempt_at),
+                last_error = ?2
+            WHERE id = ?3
+            ",
+        )
+        .bind(next_attempt_at)
+        .bind(error)
+        .bind(entry_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn sync_saved_searches(&self, searches: &[SavedSearch]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let now = Utc::now();
+
+        let stored: Vec<String> = sqlx::query_scalar("SELECT name FROM saved_searches")
+            .fetch_all(&mut *tx)
+            .await?;
+        for name in stored {
+            if !searches.iter().any(|search| search.name == name) {
+                sqlx::query("DELETE FROM saved_searches WHERE name = ?")
+                    .bind(&name)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        for search in searches {
+            sqlx::query(
+                r"
+                INSERT INTO saved_searches (
+                    name, keywords, exclude_keywords, brands, exclude_brands,
+                    min_price, max_price, currency, sizes, sites, notify, created_at, updated_at
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?12)
+                ON CONFLICT (name) DO UPDATE SET
+                    keywords = excluded.keywords,
+                    exclude_keywords = excluded.exclude_keywords,
+                    brands = excluded.brands,
+                    exclude_brands = excluded.exclude_brands,
+                    min_price = excluded.min_price,
+                    max_price = excluded.max_price,
+                    currency = excluded.currency,
+                    sizes = excluded.sizes,
+                    sites = excluded.sites,
+                    notify = excluded.notify,
+                    updated_at = excluded.updated_at
+                ",
+            )
+            .bind(&search.name)
+            .bind(serde_json::to_string(&search.keywords)?)
+            .bind(serde_json::to_string(&search.exclude_keywords)?)
+            .bind(serde_json::to_string(&search.brands)?)
+            .bind(serde_json::to_string(&search.exclude_brands)?)
+            .bind(search.min_price.map(|price| price.to_string()))
+            .bind(search.max_price.map(|price| price.to_string()))
+            .bind(&search.currency)
+            .bind(serde_json::to_string(&search.sizes)?)
+            .bind(serde_json::to_string(&search.sites)?)
+            .bind(serde_json::to_string(&search.notify)?)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn get_saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        let rows = sqlx::query(
+            r"
+            SELECT name, keywords, exclude_keywords, brands, exclude_brands,
+                   min_price, max_price, currency, sizes, sites, notify
+            FROM saved_searches
+            ORDER BY name
+            ",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let name: String = row.get("name");
+                let list = |column: &str| -> Result<Vec<String>> {
+                    serde_json::from_str(row.get(column))
+                        .with_context(|| format!("Invalid {column} in saved search '{name}'"))
+                };
+                let decimal = |column: &str| -> Result<Option<Decimal>> {
+                    row.get::<Option<String>, _>(column)
+                        .map(|value| Decimal::from_str(&value))
+                        .transpose()
+                        .with_context(|| format!("Invalid {column} in saved search '{name}'"))
+                };
+
+                Ok(SavedSearch {
+                    keywords: list("keywords")?,
+                    exclude_keywords: list("exclude_keywords")?,
+                    brands: list("brands")?,
+                    exclude_brands: list("exclude_brands")?,
+                    min_price: decimal("min_price")?,
+                    max_price: decimal("max_price")?,
+                    currency: row.get("currency"),
+                    sizes: list("sizes")?,
+                    sites: list("sites")?,
+                    notify: serde_json::from_str(row.get("notify"))
+                        .with_context(|| format!("Invalid notify in saved search '{name}'"))?,
+                    name,
+                })
+            })
+            .collect()
+    }
+
+    async fn list_jackets(&self, filter: &ListingFilter) -> Result<Vec<Jacket>> {
+        let rows = sqlx::query(
+            r"
+            SELECT id, source, title, brand, price, price_amount_minor, price_currency, url, image_url,
+                   discovered_at, size, measurements, condition, description, status
+            FROM jackets
+            WHERE (?1 IS NULL OR source = ?1)
+              AND (?2 IS NULL OR status = ?2)
+              AND (?3 IS NULL OR title LIKE '%' || ?3 || '%')
+            ORDER BY discovered_at DESC
+            ",
+        )
+        .bind(&filter.source)
+        .bind(filter.status.map(ListingStatus::as_str))
+        .bind(&filter.title)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let jackets = rows
+            .into_iter()
+            .map(|row| Jacket {
+                id: row.get("id"),
+                source: row.get::<Option<String>, _>("source").unwrap_or_default(),
+                title: row.get("title"),
+                brand: row.get("brand"),
+                price: Price::from_parts(row.get("price"), row.get("price_amount_minor"), row.get("price_currency")),
+                url: row.get("url"),
+                image_url: row.get("image_url"),
+                discovered_at: row.get("discovered_at"),
+                size: row.get("size"),
+                measurements: row.get("measurements"),
+                condition: row.get("condition"),
+                description: row.get("description"),
+                status: ListingStatus::from_db(row.get("status")),
+            })
+            .collect();
+
+        Ok(jackets)
+    }
+
+    async fn stats(&self) -> Result<DatabaseStats> {
+        let grouped = |sql: &'static str| async move {
+            sqlx::query_as::<_, (String, i64)>(sql).fetch_all(&self.pool).await
+        };
+
+        Ok(DatabaseStats {
+            listings_by_status: grouped("SELECT status, COUNT(*) FROM jackets GROUP BY status ORDER BY status").await?,
+            listings_by_source: grouped(
+                "SELECT COALESCE(source, 'unknown'), COUNT(*) FROM jackets GROUP BY source ORDER BY source",
+            )
+            .await?,
+            price_observations: sqlx::query_scalar("SELECT COUNT(*) FROM price_history")
+                .fetch_one(&self.pool)
+                .await?,
+            saved_searches: sqlx::query_scalar("SELECT COUNT(*) FROM saved_searches")
+                .fetch_one(&self.pool)
+                .await?,
+            outbox_by_status: grouped(
+                "SELECT status, COUNT(*) FROM notification_outbox GROUP BY status ORDER BY status",
+            )
+            .await?,
+            scrape_runs: sqlx::query_scalar("SELECT COUNT(*) FROM scrape_runs")
+                .fetch_one(&self.pool)
+                .await?,
+        })
+    }
+
+    async fn record_scrape_run(
+        &self,
+        started_at: DateTime<Utc>,
+        finished_at: DateTime<Utc>,
+        scrapers: &[ScraperRun],
+    ) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+
+        let run_id: i64 =
+            sqlx::query_scalar("INSERT INTO scrape_runs (started_at, finished_at) VALUES (?, ?) RETURNING id")
+                .bind(started_at)
+                .bind(finished_at)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        for scraper in scrapers {
+            sqlx::query(
+                r"
+                INSERT INTO scrape_run_scrapers (
+                    run_id, scraper, started_at, finished_at,
+                    pages_fetched, items_parsed, items_matched, missing_titles, missing_prices,
+                    pages_disallowed, new_items, error, warnings
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ",
+            )
+            .bind(run_id)
+            .bind(&scraper.scraper)
+            .bind(scraper.started_at)
+            .bind(scraper.finished_at)
+            .bind(scraper.stats.pages_fetched)
+            .bind(scraper.stats.items_parsed)
+            .bind(scraper.stats.items_matched)
+            .bind(scraper.stats.missing_titles)
+            .bind(scraper.stats.missing_prices)
+            .bind(scraper.stats.pages_disallowed)
+            .bind(scraper.new_items)
+            .bind(&scraper.error)
+            .bind(serde_json::to_string(&scraper.warnings)?)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(run_id)
+    }
+
+    async fn recent_scrape_runs(&self, limit: u32) -> Result<Vec<ScrapeRun>> {
+        let runs: Vec<(i64, DateTime<Utc>, DateTime<Utc>)> =
+            sqlx::query_as("SELECT id, started_at, finished_at FROM scrape_runs ORDER BY id DESC LIMIT ?")
+                .bind(limit)
+                .fetch_all(&self.pool)
+                .await?;
+        let Some(oldest) = runs.last().map(|(id, _, _)| *id) else {
+            return Ok(Vec::new());
+        };
+
+        let rows = sqlx::query(
+            r"
+            SELECT run_id, scraper, started_at, finished_at,
+                   pages_fetched, items_parsed, items_matched, missing_titles, missing_prices,
+                   pages_disallowed, new_items, error, warnings
+            FROM scrape_run_scrapers
+            WHERE run_id >= ?
+            ORDER BY id
+            ",
+        )
+        .bind(oldest)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let scrapers = rows
+            .into_iter()
+            .map(|row| {
+                let name: String = row.get("scraper");
+                let warnings = serde_json::from_str(row.get("warnings"))
+                    .with_context(|| format!("Invalid warnings in scrape run of '{name}'"))?;
+                let scraper = ScraperRun {
+                    scraper: name,
+                    started_at: row.get("started_at"),
+                    finished_at: row.get("finished_at"),
+                    stats: ScrapeStats {
+                        pages_fetched: row.get("pages_fetched"),
+                        items_parsed: row.get("items_parsed"),
+                        items_matched: row.get("items_matched"),
+                        missing_titles: row.get("missing_titles"),
+                        missing_prices: row.get("missing_prices"),
+                        pages_disallowed: row.get("pages_disallowed"),
+                    },
+                    new_items: row.get("new_items"),
+                    error: row.get("error"),
+                    warnings,
+                };
+                Ok((row.get("run_id"), scraper))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(assemble_runs(runs, scrapers))
+    }
+
+    async fn mark_missing(&self, source: &str, seen_since: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE jackets SET status = 'missing' WHERE source = ? AND status = 'available' AND last_seen < ?",
+        )
+        .bind(source)
+        .bind(seen_since)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Build the options every pooled connection is opened with
+///
+/// # Arguments
+/// * `config` - Database location and connection settings
+/// * `read_only` - Open without write access, leaving the journal mode as it is
+///
+/// # Returns
+/// * `Result<SqliteConnectOptions>` - Connection options or an error for an invalid URL
+fn connect_options(config: &DatabaseConfig, read_only: bool) -> Result<SqliteConnectOptions> {
+    let options = SqliteConnectOptions::from_str(&config.url)
+        .with_context(|| format!("Invalid DATABASE_URL {:?}", config.url))?
+        .busy_timeout(config.busy_timeout)
+        .synchronous(config.synchronous);
+
+    if read_only {
+        return Ok(options.read_only(true));
+    }
+
+    let options = options.create_if_missing(true);
+    Ok(match (config.is_in_memory(), config.wal) {
+        // In-memory databases have no journal file
+        (true, _) => options,
+        (false, true) => options.journal_mode(SqliteJournalMode::Wal),
+        (false, false) => options.journal_mode(SqliteJournalMode::Delete),
+    })
+}
+
+/// Build the pool settings
+fn pool_options(config: &DatabaseConfig) -> SqlitePoolOptions {
+    if config.is_in_memory() {
+        // The database lives only as long as a connection to it, so keep exactly one open for good
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+    } else {
+        SqlitePoolOptions::new().max_connections(config.max_connections)
+    }
+}
+
+/// Append a price observation to the price history
+async fn insert_price_history(
+    tx: &mut Transaction<'_, Sqlite>,
+    jacket_id: &str,
+    price: &Price,
+    observed_at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query(
+        r"
+        INSERT INTO price_history (jacket_id, price, price_amount_minor, price_currency, observed_at)
+        VALUES (?, ?, ?, ?, ?)
+        ",
+    )
+    .bind(jacket_id)
+    .bind(&price.raw)
+    .bind(price.amount_minor())
+    .bind(&price.currency)
+    .bind(observed_at)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Queue an event for each notifier
+async fn insert_outbox_entries(
+    tx: &mut Transaction<'_, Sqlite>,
+    event: &NotificationEvent,
+    notifiers: &[String],
+    created_at: DateTime<Utc>,
+) -> Result<()> {
+    let payload = serde_json::to_string(event)?;
+
+    for notifier in notifiers {
+        sqlx::query(
+            r"
+            INSERT INTO notification_outbox (notifier, jacket_id, event, next_attempt_at, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(notifier)
+        .bind(event.jacket_id())
+        .bind(&payload)
+        .bind(created_at)
+        .bind(created_at)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    Ok(())
+}
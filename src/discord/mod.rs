@@ -1,89 +1,534 @@
 //! Discord webhook notifications for jacket discoveries
 
-use anyhow::Result;
-use reqwest::Client;
-use tracing::{error, info, warn};
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{Instant, sleep, sleep_until};
+use tracing::{info, warn};
 
+use crate::config::env_bool;
 use crate::models::{
     DiscordEmbed, DiscordField, DiscordImage, DiscordMessage, DiscordThumbnail, Jacket,
+    NotificationEvent, PriceDrop,
 };
+use crate::notifiers::{Destination, summarize, truncate};
+use crate::traits::Notifier;
+
+/// Maximum length of an embed field value accepted by Discord
+const FIELD_VALUE_LIMIT: usize = 1024;
+
+/// Maximum length of an embed description accepted by Discord
+const DESCRIPTION_LIMIT: usize = 4096;
+
+/// Maximum number of embeds in a single message accepted by Discord
+const EMBEDS_PER_MESSAGE: usize = 10;
+
+/// Maximum combined text length of all embeds in a single message accepted by Discord
+const EMBED_TEXT_PER_MESSAGE: usize = 6000;
+
+/// Maximum number of attempts for a single webhook request
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Delay before retrying a failed request, doubled after every further failure
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest rate limit wait honoured before giving up on a request
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// Body of a Discord 429 response
+#[derive(Debug, Deserialize)]
+struct RateLimitResponse {
+    /// Seconds to wait before retrying
+    retry_after: f64,
+}
 
 /// Discord webhook client for sending jacket notifications
 pub struct DiscordNotifier {
     client: Client,
-    webhook_url: Option<String>,
+    webhook_url: String,
+    /// When the webhook's rate limit bucket refills, if it is currently exhausted
+    rate_limit_reset: Arc<Mutex<Option<Instant>>>,
+    /// Whether a batch of events is combined into messages of up to ten embeds
+    batch_embeds: bool,
 }
 
 impl DiscordNotifier {
-    /// Create a new Discord notifier from environment configuration
+    /// Create a new Discord notifier posting to a webhook
+    /// 
+    /// # Arguments
+    /// * `webhook_url` - Discord webhook URL
     /// 
     /// # Returns
-    /// * `Self` - New `DiscordNotifier` instance with optional webhook configuration
-    pub fn new() -> Self {
-        let client = Client::new();
-        let webhook_url = std::env::var("DISCORD_WEBHOOK_URL").ok();
-
-        if webhook_url.is_none() {
-            warn!("DISCORD_WEBHOOK_URL not set - Discord notifications will be disabled");
-        }
-
+    /// * `Self` - New `DiscordNotifier` instance
+    pub fn new(webhook_url: String) -> Self {
         Self {
-            client,
+            client: Client::new(),
             webhook_url,
+            rate_limit_reset: Arc::new(Mutex::new(None)),
+            batch_embeds: true,
         }
     }
 
-    /// Send a Discord notification for a newly discovered jacket
+    /// Choose between one message per batch of events and one message per event
     /// 
     /// # Arguments
-    /// * `jacket` - Reference to the jacket data to include in the notification
+    /// * `batch_embeds` - `true` to combine events into messages of up to ten embeds
     /// 
     /// # Returns
-    /// * `Result<()>` - Success or network/serialization error
-    pub async fn send_notification(&self, jacket: &Jacket) -> Result<()> {
-        if let Some(webhook_url) = &self.webhook_url {
-            let embed = DiscordEmbed {
-                title: "🧥 New N-1 Deck Jacket Found!".to_string(),
-                description: jacket.title.clone(),
-                url: jacket.url.clone(),
-                color: 0x0058_65F2, // Discord blue
-                timestamp: jacket.discovered_at.to_rfc3339(),
-                thumbnail: jacket
-                    .image_url
-                    .as_ref()
-                    .map(|url| DiscordThumbnail { url: url.clone() }),
-                image: jacket
-                    .image_url
-                    .as_ref()
-                    .map(|url| DiscordImage { url: url.clone() }),
-                fields: vec![
-                    DiscordField {
-                        name: "Price".to_string(),
-                        value: jacket.price.clone(),
-                        inline: true,
-                    },
-                    DiscordField {
-                        name: "Link".to_string(),
-                        value: format!("[View on Marrkt]({})", jacket.url),
-                        inline: true,
-                    },
-                ],
-            };
+    /// * `Self` - The notifier using the given mode
+    pub fn with_batch_embeds(mut self, batch_embeds: bool) -> Self {
+        self.batch_embeds = batch_embeds;
+        self
+    }
+
+    /// Create a Discord notifier from environment configuration
+    /// 
+    /// # Returns
+    /// * `Result<Option<Self>>` - Notifier for `DISCORD_WEBHOOK_URL`, `None` if it is not set,
+    ///   or an error for an invalid `DISCORD_BATCH_EMBEDS`
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(webhook_url) = std::env::var("DISCORD_WEBHOOK_URL") else {
+            warn!("DISCORD_WEBHOOK_URL not set - Discord notifications will be disabled");
+            return Ok(None);
+        };
+
+        Self::for_webhook(webhook_url).map(Some)
+    }
+
+    /// Create a Discord notifier for a specific webhook, using the environment for other settings
+    /// 
+    /// # Arguments
+    /// * `webhook_url` - Discord webhook URL
+    /// 
+    /// # Returns
+    /// * `Result<Self>` - New notifier or an error for an invalid `DISCORD_BATCH_EMBEDS`
+    pub fn for_webhook(webhook_url: String) -> Result<Self> {
+        let batch_embeds = env_bool("DISCORD_BATCH_EMBEDS", true)?;
+        Ok(Self::new(webhook_url).with_batch_embeds(batch_embeds))
+    }
 
+    /// Send every event in as few messages as possible, headed by a summary
+    /// 
+    /// Embeds are packed into messages of up to ten embeds within Discord's combined
+    /// text limit, and the first message carries a summary of the whole batch.
+    /// 
+    /// # Arguments
+    /// * `events` - Events to send, in discovery order
+    /// 
+    /// # Returns
+    /// * `Vec<Result<()>>` - One result per event, shared by all events in the same message
+    async fn send_batched(&self, events: &[NotificationEvent]) -> Vec<Result<()>> {
+        let mut results = Vec::with_capacity(events.len());
+        let mut content = (events.len() > 1).then(|| format!("**Jacket finder:** {}", summarize(events)));
+
+        for chunk in chunk_embeds(events.iter().map(event_embed).collect()) {
+            let count = chunk.len();
             let message = DiscordMessage {
-                embeds: vec![embed],
+                content: content.take(),
+                embeds: chunk,
+            };
+
+            match self.post(&message).await {
+                Ok(()) => {
+                    info!("Discord message sent with {} notifications", count);
+                    results.extend((0..count).map(|_| Ok(())));
+                }
+                Err(e) => {
+                    let message = format!("Discord rejected message with {count} notifications: {e:#}");
+                    results.extend((0..count).map(|_| Err(anyhow!("{message}"))));
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Send a single event as its own message
+    /// 
+    /// # Arguments
+    /// * `event` - The event to send
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Success, or an error once the message could not be delivered
+    async fn send_single(&self, event: &NotificationEvent) -> Result<()> {
+        let (kind, subject) = match event {
+            NotificationEvent::NewListing(jacket) => ("jacket", jacket.title.as_str()),
+            NotificationEvent::PriceDrop(drop) => ("price drop", drop.jacket.title.as_str()),
+            NotificationEvent::BackInStock(jacket) => ("back in stock", jacket.title.as_str()),
+            NotificationEvent::Error { context, .. } => ("error", context.as_str()),
+            NotificationEvent::ScraperUnhealthy { scraper, .. } => ("health", scraper.as_str()),
+        };
+
+        let message = DiscordMessage {
+            content: None,
+            embeds: vec![event_embed(event)],
+        };
+
+        self.post(&message)
+            .await
+            .with_context(|| format!("Discord rejected {kind} notification"))?;
+
+        info!("Discord {} notification sent for: {}", kind, subject);
+        Ok(())
+    }
+
+    /// Post a message to the webhook, waiting out rate limits and retrying transient failures
+    /// 
+    /// Requests wait while the webhook's rate limit bucket is exhausted. A 429 response is
+    /// retried after its `Retry-After`, network errors and 5xx responses are retried with
+    /// exponential backoff, and any other response fails immediately.
+    /// 
+    /// # Arguments
+    /// * `message` - The message to post
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Success, or the last error after `MAX_ATTEMPTS` attempts
+    async fn post(&self, message: &DiscordMessage) -> Result<()> {
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            self.wait_for_rate_limit().await;
+
+            let response = match self.client.post(&self.webhook_url).json(message).send().await {
+                Ok(response) => response,
+                Err(e) if attempt < MAX_ATTEMPTS => {
+                    warn!("Discord request failed (attempt {attempt}/{MAX_ATTEMPTS}), retrying in {backoff:?}: {e}");
+                    sleep(backoff).await;
+                    backoff *= 2;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
             };
 
-            let response = self.client.post(webhook_url).json(&message).send().await?;
+            let status = response.status();
+            self.update_rate_limit(response.headers());
+
+            if status.is_success() {
+                return Ok(());
+            }
+            if attempt >= MAX_ATTEMPTS {
+                bail!("{status} after {MAX_ATTEMPTS} attempts");
+            }
+
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                let headers = response.headers().clone();
+                let body = response.text().await.unwrap_or_default();
+                let wait = rate_limit_wait(&headers, &body).unwrap_or(backoff);
+                if wait > MAX_RATE_LIMIT_WAIT {
+                    bail!("rate limited for {wait:?}");
+                }
 
-            if response.status().is_success() {
-                info!("Discord notification sent for jacket: {}", jacket.title);
+                warn!("Discord rate limited (attempt {attempt}/{MAX_ATTEMPTS}), retrying in {wait:?}");
+                self.set_rate_limit_reset(Instant::now() + wait);
+            } else if status.is_server_error() {
+                warn!("Discord returned {status} (attempt {attempt}/{MAX_ATTEMPTS}), retrying in {backoff:?}");
+                sleep(backoff).await;
+                backoff *= 2;
             } else {
-                error!("Failed to send Discord notification: {}", response.status());
+                bail!("{status}");
             }
         }
+    }
 
-        Ok(())
+    /// Sleep until the webhook's rate limit bucket refills, if it is exhausted
+    async fn wait_for_rate_limit(&self) {
+        let reset = *self.rate_limit_reset_lock();
+        if let Some(reset) = reset
+            && reset > Instant::now()
+        {
+            info!("Waiting {:?} for Discord rate limit", reset - Instant::now());
+            sleep_until(reset).await;
+        }
+    }
+
+    /// Remember when the bucket refills once `X-RateLimit-Remaining` reaches zero
+    /// 
+    /// # Arguments
+    /// * `headers` - Headers of a webhook response
+    fn update_rate_limit(&self, headers: &HeaderMap) {
+        if let Some(reset_after) = bucket_reset_after(headers) {
+            self.set_rate_limit_reset(Instant::now() + reset_after);
+        }
+    }
+
+    /// Hold back requests until a point in time
+    fn set_rate_limit_reset(&self, reset: Instant) {
+        let mut current = self.rate_limit_reset_lock();
+        if current.is_none_or(|current| current < reset) {
+            *current = Some(reset);
+        }
+    }
+
+    /// Lock the rate limit state, recovering from a poisoned lock
+    fn rate_limit_reset_lock(&self) -> std::sync::MutexGuard<'_, Option<Instant>> {
+        self.rate_limit_reset
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Read how long a 429 response asks to wait
+/// 
+/// # Arguments
+/// * `headers` - Headers of the rate limited response
+/// * `body` - Body of the rate limited response
+/// 
+/// # Returns
+/// * `Option<Duration>` - Wait from `Retry-After`, `X-RateLimit-Reset-After` or the JSON body,
+///   in that order, or `None` if the response does not say
+fn rate_limit_wait(headers: &HeaderMap, body: &str) -> Option<Duration> {
+    header_seconds(headers, "retry-after")
+        .or_else(|| header_seconds(headers, "x-ratelimit-reset-after"))
+        .or_else(|| {
+            let body: RateLimitResponse = serde_json::from_str(body).ok()?;
+            seconds(body.retry_after)
+        })
+}
+
+/// Read when the webhook's rate limit bucket refills, once `X-RateLimit-Remaining` reaches zero
+/// 
+/// # Arguments
+/// * `headers` - Headers of a webhook response
+/// 
+/// # Returns
+/// * `Option<Duration>` - Wait from `X-RateLimit-Reset-After`, or `None` while requests remain
+fn bucket_reset_after(headers: &HeaderMap) -> Option<Duration> {
+    let exhausted = header_value(headers, "x-ratelimit-remaining").is_some_and(|remaining| remaining <= 0.0);
+    if !exhausted {
+        return None;
+    }
+    header_seconds(headers, "x-ratelimit-reset-after")
+}
+
+/// Parse a numeric response header
+fn header_value(headers: &HeaderMap, name: &str) -> Option<f64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// Parse a response header holding a number of seconds
+fn header_seconds(headers: &HeaderMap, name: &str) -> Option<Duration> {
+    header_value(headers, name).and_then(seconds)
+}
+
+/// Convert a number of seconds from Discord into a duration
+fn seconds(value: f64) -> Option<Duration> {
+    Duration::try_from_secs_f64(value).ok()
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &'static str {
+        "Discord"
+    }
+
+    fn route(&self) -> String {
+        Destination::Discord(self.webhook_url.clone()).key()
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> Result<()> {
+        self.send_single(event).await
+    }
+
+    async fn notify_batch(&self, events: &[NotificationEvent]) -> Vec<Result<()>> {
+        if self.batch_embeds {
+            return self.send_batched(events).await;
+        }
+
+        let mut results = Vec::with_capacity(events.len());
+        for event in events {
+            results.push(self.send_single(event).await);
+        }
+        results
+    }
+}
+
+/// Build the embed describing an event
+/// 
+/// # Arguments
+/// * `event` - The event to describe
+/// 
+/// # Returns
+/// * `DiscordEmbed` - Embed for the event
+fn event_embed(event: &NotificationEvent) -> DiscordEmbed {
+    match event {
+        NotificationEvent::NewListing(jacket) => {
+            listing_embed(jacket, "🧥 New N-1 Deck Jacket Found!", 0x0058_65F2) // Discord blue
+        }
+        NotificationEvent::PriceDrop(drop) => price_drop_embed(drop),
+        NotificationEvent::BackInStock(jacket) => {
+            listing_embed(jacket, "🔁 Back in Stock!", 0x00FE_E75C) // Discord yellow
+        }
+        NotificationEvent::Error { context, message } => DiscordEmbed {
+            title: format!("⚠️ Error: {context}"),
+            description: truncate(message, DESCRIPTION_LIMIT),
+            url: None,
+            color: 0x00ED_4245, // Discord red
+            timestamp: Utc::now().to_rfc3339(),
+            thumbnail: None,
+            image: None,
+            fields: Vec::new(),
+        },
+        NotificationEvent::ScraperUnhealthy { scraper, problems } => {
+            let bullets: Vec<String> = problems.iter().map(|problem| format!("• {problem}")).collect();
+            DiscordEmbed {
+                title: format!("🩺 Scraper unhealthy: {scraper}"),
+                description: truncate(&bullets.join("\n"), DESCRIPTION_LIMIT),
+                url: None,
+                color: 0x00E6_7E22, // Orange
+                timestamp: Utc::now().to_rfc3339(),
+                thumbnail: None,
+                image: None,
+                fields: Vec::new(),
+            }
+        }
+    }
+}
+
+/// Build the embed for a known jacket that became cheaper
+/// 
+/// # Arguments
+/// * `drop` - The listing with its old and new price
+/// 
+/// # Returns
+/// * `DiscordEmbed` - Embed with the old price, new price and drop percentage
+fn price_drop_embed(drop: &PriceDrop) -> DiscordEmbed {
+    let jacket = &drop.jacket;
+    DiscordEmbed {
+        title: "📉 Price Dropped!".to_string(),
+        description: jacket.title.clone(),
+        url: Some(jacket.url.clone()),
+        color: 0x0057_F287, // Discord green
+        timestamp: jacket.discovered_at.to_rfc3339(),
+        thumbnail: jacket
+            .image_url
+            .as_ref()
+            .map(|url| DiscordThumbnail { url: url.clone() }),
+        image: None,
+        fields: vec![
+            DiscordField {
+                name: "Old Price".to_string(),
+                value: format!("~~{}~~", drop.old_price),
+                inline: true,
+            },
+            DiscordField {
+                name: "New Price".to_string(),
+                value: jacket.price.to_string(),
+                inline: true,
+            },
+            DiscordField {
+                name: "Drop".to_string(),
+                value: format!("-{}%", drop.percent),
+                inline: true,
+            },
+            DiscordField {
+                name: "Link".to_string(),
+                value: format!("[View on {}]({})", jacket.source, jacket.url),
+                inline: false,
+            },
+        ],
+    }
+}
+
+/// Split embeds into message-sized groups, keeping their order
+/// 
+/// # Arguments
+/// * `embeds` - Embeds to send
+/// 
+/// # Returns
+/// * `Vec<Vec<DiscordEmbed>>` - Groups of at most `EMBEDS_PER_MESSAGE` embeds whose
+///   combined text stays within `EMBED_TEXT_PER_MESSAGE`
+fn chunk_embeds(embeds: Vec<DiscordEmbed>) -> Vec<Vec<DiscordEmbed>> {
+    let mut chunks: Vec<Vec<DiscordEmbed>> = Vec::new();
+    let mut chunk_text = 0;
+
+    for embed in embeds {
+        let text = embed_text_length(&embed);
+        match chunks.last_mut() {
+            Some(chunk) if chunk.len() < EMBEDS_PER_MESSAGE && chunk_text + text <= EMBED_TEXT_PER_MESSAGE => {
+                chunk.push(embed);
+                chunk_text += text;
+            }
+            _ => {
+                chunks.push(vec![embed]);
+                chunk_text = text;
+            }
+        }
+    }
+
+    chunks
+}
+
+/// Count the characters Discord includes in its combined embed text limit
+fn embed_text_length(embed: &DiscordEmbed) -> usize {
+    embed.title.chars().count()
+        + embed.description.chars().count()
+        + embed
+            .fields
+            .iter()
+            .map(|field| field.name.chars().count() + field.value.chars().count())
+            .sum::<usize>()
+}
+
+/// Build the rich embed describing a single listing
+/// 
+/// # Arguments
+/// * `jacket` - The listing to describe
+/// * `title` - Embed title
+/// * `color` - Embed accent color
+/// 
+/// # Returns
+/// * `DiscordEmbed` - Embed with price, link, image and any detail fields
+fn listing_embed(jacket: &Jacket, title: &str, color: u32) -> DiscordEmbed {
+    let mut fields = vec![
+        DiscordField {
+            name: "Price".to_string(),
+            value: jacket.price.to_string(),
+            inline: true,
+        },
+        DiscordField {
+            name: "Link".to_string(),
+            value: format!("[View on {}]({})", jacket.source, jacket.url),
+            inline: true,
+        },
+    ];
+
+    // Detail fields are only present when the enrichment pass found them
+    let details = [
+        ("Size", &jacket.size, true),
+        ("Condition", &jacket.condition, true),
+        ("Measurements", &jacket.measurements, false),
+        ("Description", &jacket.description, false),
+    ];
+    fields.extend(details.into_iter().filter_map(|(name, value, inline)| {
+        value.as_ref().map(|value| DiscordField {
+            name: name.to_string(),
+            value: truncate(value, FIELD_VALUE_LIMIT),
+            inline,
+        })
+    }));
+
+    DiscordEmbed {
+        title: title.to_string(),
+        description: jacket.title.clone(),
+        url: Some(jacket.url.clone()),
+        color,
+        timestamp: jacket.discovered_at.to_rfc3339(),
+        thumbnail: jacket
+            .image_url
+            .as_ref()
+            .map(|url| DiscordThumbnail { url: url.clone() }),
+        image: jacket
+            .image_url
+            .as_ref()
+            .map(|url| DiscordImage { url: url.clone() }),
+        fields,
     }
 }
 
@@ -92,6 +537,100 @@ impl Clone for DiscordNotifier {
         Self {
             client: self.client.clone(),
             webhook_url: self.webhook_url.clone(),
+            rate_limit_reset: Arc::clone(&self.rate_limit_reset),
+            batch_embeds: self.batch_embeds,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Embed whose text counts `length` characters toward the message limit
+    fn embed(length: usize) -> DiscordEmbed {
+        DiscordEmbed {
+            title: String::new(),
+            description: "x".repeat(length),
+            url: None,
+            color: 0,
+            timestamp: String::new(),
+            thumbnail: None,
+            image: None,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Headers with the given values
+    fn headers(values: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for &(name, value) in values {
+            headers.insert(name, value.parse().expect("valid header value"));
+        }
+        headers
+    }
+
+    /// Number of embeds in each message
+    fn chunk_sizes(embeds: Vec<DiscordEmbed>) -> Vec<usize> {
+        chunk_embeds(embeds).iter().map(Vec::len).collect()
+    }
+
+    #[test]
+    fn ten_embeds_fit_one_message() {
+        assert_eq!(chunk_sizes((0..10).map(|_| embed(10)).collect()), [10]);
+    }
+
+    #[test]
+    fn eleventh_embed_starts_a_new_message() {
+        assert_eq!(chunk_sizes((0..11).map(|_| embed(10)).collect()), [10, 1]);
+    }
+
+    #[test]
+    fn embed_crossing_the_text_limit_starts_a_new_message() {
+        assert_eq!(chunk_sizes(vec![embed(3000), embed(3000)]), [2]);
+        assert_eq!(chunk_sizes(vec![embed(2500), embed(2500), embed(1001), embed(10)]), [2, 2]);
+    }
+
+    #[test]
+    fn chunks_keep_embed_order() {
+        let chunks = chunk_embeds(vec![embed(4000), embed(3000), embed(1)]);
+        let lengths: Vec<Vec<usize>> = chunks
+            .iter()
+            .map(|chunk| chunk.iter().map(embed_text_length).collect())
+            .collect();
+        assert_eq!(lengths, [vec![4000], vec![3000, 1]]);
+    }
+
+    #[test]
+    fn rate_limit_wait_prefers_retry_after() {
+        let both = headers(&[("retry-after", "2"), ("x-ratelimit-reset-after", "5.5")]);
+        assert_eq!(rate_limit_wait(&both, ""), Some(Duration::from_secs(2)));
+
+        let reset_only = headers(&[("x-ratelimit-reset-after", "5.5")]);
+        assert_eq!(rate_limit_wait(&reset_only, ""), Some(Duration::from_millis(5500)));
+    }
+
+    #[test]
+    fn rate_limit_wait_falls_back_to_the_body() {
+        let body = r#"{"message": "You are being rate limited.", "retry_after": 0.75, "global": false}"#;
+        assert_eq!(rate_limit_wait(&HeaderMap::new(), body), Some(Duration::from_millis(750)));
+        assert_eq!(rate_limit_wait(&headers(&[("retry-after", "soon")]), body), Some(Duration::from_millis(750)));
+    }
+
+    #[test]
+    fn rate_limit_wait_ignores_missing_and_invalid_values() {
+        assert_eq!(rate_limit_wait(&HeaderMap::new(), ""), None);
+        assert_eq!(rate_limit_wait(&headers(&[("retry-after", "-1")]), "not json"), None);
+        assert_eq!(rate_limit_wait(&HeaderMap::new(), r#"{"retry_after": -1}"#), None);
+    }
+
+    #[test]
+    fn bucket_resets_only_once_exhausted() {
+        let exhausted = headers(&[("x-ratelimit-remaining", "0"), ("x-ratelimit-reset-after", "1.25")]);
+        assert_eq!(bucket_reset_after(&exhausted), Some(Duration::from_millis(1250)));
+
+        let remaining = headers(&[("x-ratelimit-remaining", "3"), ("x-ratelimit-reset-after", "1.25")]);
+        assert_eq!(bucket_reset_after(&remaining), None);
+        assert_eq!(bucket_reset_after(&headers(&[("x-ratelimit-remaining", "0")])), None);
+    }
+}
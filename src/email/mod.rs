@@ -0,0 +1,421 @@
+//! SMTP email digests of jacket discoveries
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::fmt::Write;
+use tracing::info;
+
+use crate::models::{Jacket, NotificationEvent};
+use crate::notifiers::{Destination, escape_html, summarize};
+use crate::traits::Notifier;
+
+/// How the SMTP connection is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Plain connection, e.g. a local SMTP sink
+    None,
+    /// Upgrade a plain connection with `STARTTLS`
+    StartTls,
+    /// TLS from the first byte (SMTPS)
+    Tls,
+}
+
+impl SmtpTls {
+    /// Parse a TLS mode from configuration
+    ///
+    /// # Arguments
+    /// * `value` - One of `none`, `starttls` or `tls`
+    ///
+    /// # Returns
+    /// * `Result<Self>` - The TLS mode or an error naming the accepted values
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "starttls" => Ok(Self::StartTls),
+            "tls" => Ok(Self::Tls),
+            other => bail!("Invalid SMTP_TLS {other:?}, expected \"none\", \"starttls\" or \"tls\""),
+        }
+    }
+
+    /// Get the conventional port for this TLS mode
+    pub fn default_port(self) -> u16 {
+        match self {
+            Self::None => 25,
+            Self::StartTls => 587,
+            Self::Tls => 465,
+        }
+    }
+}
+
+/// SMTP server and addressing configuration
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: SmtpTls,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+impl SmtpConfig {
+    /// Read the SMTP configuration from the environment
+    ///
+    /// # Returns
+    /// * `Result<Option<Self>>` - Configuration when `SMTP_HOST` is set, `None` when it is not,
+    ///   or an error for incomplete or invalid settings
+    pub fn from_env() -> Result<Option<Self>> {
+        let Ok(host) = std::env::var("SMTP_HOST") else {
+            return Ok(None);
+        };
+
+        let tls = std::env::var("SMTP_TLS").map_or(Ok(SmtpTls::StartTls), |value| SmtpTls::parse(&value))?;
+        let port = match std::env::var("SMTP_PORT") {
+            Ok(port) => port
+                .trim()
+                .parse()
+                .with_context(|| format!("Invalid SMTP_PORT: {port:?}"))?,
+            Err(_) => tls.default_port(),
+        };
+        let from = std::env::var("SMTP_FROM").context("SMTP_FROM must be set when SMTP_HOST is set")?;
+        let to: Vec<String> = std::env::var("SMTP_TO")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Ok(Some(Self {
+            host,
+            port,
+            tls,
+            username: std::env::var("SMTP_USERNAME").ok(),
+            password: std::env::var("SMTP_PASSWORD").ok(),
+            from,
+            to,
+        }))
+    }
+}
+
+/// SMTP client sending one digest email per check
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailNotifier {
+    /// Create a new email notifier, validating every address up front
+    ///
+    /// # Arguments
+    /// * `config` - SMTP server and addressing configuration
+    ///
+    /// # Returns
+    /// * `Result<Self>` - New notifier or a configuration error
+    pub fn new(config: SmtpConfig) -> Result<Self> {
+        let mut builder = match config.tls {
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+        }
+        .port(config.port);
+
+        if let (Some(username), Some(password)) = (config.username, config.password) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        let from = config
+            .from
+            .parse()
+            .with_context(|| format!("Invalid SMTP_FROM address {:?}", config.from))?;
+        let to = config
+            .to
+            .iter()
+            .map(|address| {
+                address
+                    .parse()
+                    .with_context(|| format!("Invalid email address {address:?}"))
+            })
+            .collect::<Result<Vec<Mailbox>>>()?;
+
+        if to.is_empty() {
+            bail!("SMTP_TO must contain at least one address");
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+            to,
+        })
+    }
+
+    /// Send one digest email covering a set of events
+    ///
+    /// # Arguments
+    /// * `events` - Events from one check, in discovery order
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success, or an error if the email was not accepted by the server
+    async fn send_digest(&self, events: &[NotificationEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let digest = Digest::from_events(events);
+
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(format!("Jacket finder: {}", summarize(events)));
+        for recipient in &self.to {
+            builder = builder.to(recipient.clone());
+        }
+        let message = builder.multipart(MultiPart::alternative_plain_html(
+            digest.to_plain_text(),
+            digest.to_html(),
+        ))?;
+
+        self.transport.send(message).await?;
+
+        info!("Email digest sent to {} recipients with {} events", self.to.len(), events.len());
+        Ok(())
+    }
+
+    /// Create an email notifier from environment configuration
+    ///
+    /// # Returns
+    /// * `Result<Option<Self>>` - Notifier when `SMTP_HOST` and `SMTP_TO` are set, `None` when
+    ///   either is not, or an error for invalid settings
+    pub fn from_env() -> Result<Option<Self>> {
+        match SmtpConfig::from_env()? {
+            Some(config) if !config.to.is_empty() => Self::new(config).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Create an email notifier for a single recipient, using the SMTP server from the environment
+    ///
+    /// # Arguments
+    /// * `address` - Recipient address, used instead of `SMTP_TO`
+    ///
+    /// # Returns
+    /// * `Result<Self>` - New notifier, or an error if `SMTP_HOST` is unset or a setting is invalid
+    pub fn for_address(address: &str) -> Result<Self> {
+        let mut config = SmtpConfig::from_env()?
+            .context("SMTP_HOST must be set to send email notifications")?;
+        config.to = vec![address.to_string()];
+        Self::new(config)
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &'static str {
+        "Email"
+    }
+
+    fn route(&self) -> String {
+        let addresses: Vec<String> = self.to.iter().map(|mailbox| mailbox.email.to_string()).collect();
+        Destination::Email(addresses.join(",")).key()
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> Result<()> {
+        self.send_digest(std::slice::from_ref(event)).await
+    }
+
+    async fn notify_batch(&self, events: &[NotificationEvent]) -> Vec<Result<()>> {
+        // The digest is a single email, so every event shares its outcome
+        match self.send_digest(events).await {
+            Ok(()) => events.iter().map(|_| Ok(())).collect(),
+            Err(e) => {
+                let message = format!("{e:#}");
+                events.iter().map(|_| Err(anyhow!("{message}"))).collect()
+            }
+        }
+    }
+}
+
+impl Clone for EmailNotifier {
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+        }
+    }
+}
+
+/// A listing shown in the digest, with an optional note such as the old price
+struct DigestEntry<'a> {
+    jacket: &'a Jacket,
+    note: Option<String>,
+}
+
+/// Events from one check grouped into digest sections
+struct Digest<'a> {
+    new_listings: Vec<DigestEntry<'a>>,
+    price_drops: Vec<DigestEntry<'a>>,
+    back_in_stock: Vec<DigestEntry<'a>>,
+    errors: Vec<(&'a str, &'a str)>,
+    unhealthy: Vec<(&'a str, &'a [String])>,
+}
+
+impl<'a> Digest<'a> {
+    /// Group events into digest sections, keeping their order within each section
+    fn from_events(events: &'a [NotificationEvent]) -> Self {
+        let mut digest = Self {
+            new_listings: Vec::new(),
+            price_drops: Vec::new(),
+            back_in_stock: Vec::new(),
+            errors: Vec::new(),
+            unhealthy: Vec::new(),
+        };
+
+        for event in events {
+            match event {
+                NotificationEvent::NewListing(jacket) => {
+                    digest.new_listings.push(DigestEntry { jacket, note: None });
+                }
+                NotificationEvent::PriceDrop(drop) => digest.price_drops.push(DigestEntry {
+                    jacket: &drop.jacket,
+                    note: Some(format!("was {} (-{}%)", drop.old_price, drop.percent)),
+                }),
+                NotificationEvent::BackInStock(jacket) => {
+                    digest.back_in_stock.push(DigestEntry { jacket, note: None });
+                }
+                NotificationEvent::Error { context, message } => digest.errors.push((context, message)),
+                NotificationEvent::ScraperUnhealthy { scraper, problems } => {
+                    digest.unhealthy.push((scraper, problems));
+                }
+            }
+        }
+
+        digest
+    }
+
+    /// Named sections of listings, skipping empty ones
+    fn sections(&self) -> impl Iterator<Item = (&'static str, &[DigestEntry<'a>])> {
+        [
+            ("🧥 New jackets", self.new_listings.as_slice()),
+            ("📉 Price drops", self.price_drops.as_slice()),
+            ("🔁 Back in stock", self.back_in_stock.as_slice()),
+        ]
+        .into_iter()
+        .filter(|(_, entries)| !entries.is_empty())
+    }
+
+    /// Render the plain-text body
+    fn to_plain_text(&self) -> String {
+        let mut text = String::new();
+
+        for (heading, entries) in self.sections() {
+            let _ = writeln!(text, "{heading}\n");
+            for entry in entries {
+                let jacket = entry.jacket;
+                let _ = writeln!(text, "{}", jacket.title);
+                let _ = write!(text, "  {}", jacket.price);
+                if let Some(note) = &entry.note {
+                    let _ = write!(text, " ({note})");
+                }
+                let _ = writeln!(text);
+                for detail in details(jacket) {
+                    let _ = writeln!(text, "  {detail}");
+                }
+                let _ = writeln!(text, "  {}\n", jacket.url);
+            }
+        }
+
+        if !self.errors.is_empty() {
+            let _ = writeln!(text, "⚠️ Errors\n");
+            for (context, message) in &self.errors {
+                let _ = writeln!(text, "{context}: {message}");
+            }
+        }
+
+        if !self.unhealthy.is_empty() {
+            let _ = writeln!(text, "🩺 Scraper health\n");
+            for (scraper, problems) in &self.unhealthy {
+                let _ = writeln!(text, "{scraper}");
+                for problem in *problems {
+                    let _ = writeln!(text, "  {problem}");
+                }
+            }
+        }
+
+        text
+    }
+
+    /// Render the HTML body
+    fn to_html(&self) -> String {
+        let mut html = String::from("<html><body style=\"font-family: sans-serif\">");
+
+        for (heading, entries) in self.sections() {
+            let _ = write!(html, "<h2>{}</h2>", escape_html(heading));
+            for entry in entries {
+                let jacket = entry.jacket;
+                html.push_str("<table style=\"margin-bottom: 16px\"><tr>");
+                if let Some(image_url) = &jacket.image_url {
+                    let _ = write!(
+                        html,
+                        "<td style=\"vertical-align: top; padding-right: 12px\"><img src=\"{}\" width=\"160\" alt=\"\"></td>",
+                        escape_html(image_url)
+                    );
+                }
+                let _ = write!(
+                    html,
+                    "<td style=\"vertical-align: top\"><a href=\"{}\"><strong>{}</strong></a><br>{}",
+                    escape_html(&jacket.url),
+                    escape_html(&jacket.title),
+                    escape_html(&jacket.price.to_string())
+                );
+                if let Some(note) = &entry.note {
+                    let _ = write!(html, " <em>({})</em>", escape_html(note));
+                }
+                for detail in details(jacket) {
+                    let _ = write!(html, "<br>{}", escape_html(&detail));
+                }
+                let _ = write!(
+                    html,
+                    "<br><a href=\"{}\">View on {}</a></td></tr></table>",
+                    escape_html(&jacket.url),
+                    escape_html(&jacket.source)
+                );
+            }
+        }
+
+        if !self.errors.is_empty() {
+            html.push_str("<h2>⚠️ Errors</h2><ul>");
+            for (context, message) in &self.errors {
+                let _ = write!(html, "<li><strong>{}</strong>: {}</li>", escape_html(context), escape_html(message));
+            }
+            html.push_str("</ul>");
+        }
+
+        if !self.unhealthy.is_empty() {
+            html.push_str("<h2>🩺 Scraper health</h2><ul>");
+            for (scraper, problems) in &self.unhealthy {
+                let _ = write!(html, "<li><strong>{}</strong><ul>", escape_html(scraper));
+                for problem in *problems {
+                    let _ = write!(html, "<li>{}</li>", escape_html(problem));
+                }
+                html.push_str("</ul></li>");
+            }
+            html.push_str("</ul>");
+        }
+
+        html.push_str("</body></html>");
+        html
+    }
+}
+
+/// Detail lines for a listing, only for fields the enrichment pass found
+fn details(jacket: &Jacket) -> Vec<String> {
+    [("Size", &jacket.size), ("Condition", &jacket.condition)]
+        .into_iter()
+        .filter_map(|(label, value)| value.as_ref().map(|value| format!("{label}: {value}")))
+        .collect()
+}
@@ -0,0 +1,133 @@
+//! Heuristics spotting scrapers whose searches succeed but whose results look wrong
+//!
+//! When a website changes its markup, selectors quietly stop matching: searches still
+//! succeed, they just find nothing. Each successful search is compared with the scraper's
+//! earlier searches to tell a broken scraper from a quiet day.
+
+use crate::models::ScrapeStats;
+
+/// Number of recent checks whose searches form a scraper's baseline
+pub const HISTORY_RUNS: u32 = 20;
+
+/// Minimum number of earlier healthy searches before a drop in parsed items is judged
+const MIN_BASELINE_RUNS: u64 = 3;
+
+/// Minimum average number of parsed items before a drop is judged
+const MIN_BASELINE_ITEMS: u64 = 5;
+
+/// A search parsing less than this fraction of the average is a sudden drop (`1 / DROP_DIVISOR`)
+const DROP_DIVISOR: u64 = 2;
+
+/// Check a successful search for signs that the scraper no longer understands its website
+///
+/// # Arguments
+/// * `stats` - Counters of the search
+/// * `baseline` - Counters of earlier healthy searches by the same scraper
+///
+/// # Returns
+/// * `Vec<String>` - One sentence per problem found, empty if the search looks healthy
+pub fn assess(stats: &ScrapeStats, baseline: &[ScrapeStats]) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if stats.pages_fetched > 0 && stats.items_parsed == 0 {
+        let pages = if stats.pages_fetched == 1 { "page" } else { "pages" };
+        problems.push(format!(
+            "Fetched {} {pages} but found no products on any of them",
+            stats.pages_fetched
+        ));
+    }
+
+    let runs = baseline.len() as u64;
+    let total: u64 = baseline.iter().map(|earlier| u64::from(earlier.items_parsed)).sum();
+    if stats.items_parsed > 0
+        && runs >= MIN_BASELINE_RUNS
+        && total >= MIN_BASELINE_ITEMS * runs
+        && u64::from(stats.items_parsed) * runs * DROP_DIVISOR < total
+    {
+        problems.push(format!(
+            "Found {} products, down from an average of {}",
+            stats.items_parsed,
+            total / runs
+        ));
+    }
+
+    if stats.pages_disallowed > 0 {
+        let pages = if stats.pages_disallowed == 1 { "page" } else { "pages" };
+        problems.push(format!(
+            "Skipped {} {pages} that robots.txt disallows",
+            stats.pages_disallowed
+        ));
+    }
+
+    if is_majority(stats.missing_titles, stats.items_parsed) {
+        problems.push(format!(
+            "{} of {} products had no title",
+            stats.missing_titles, stats.items_parsed
+        ));
+    }
+
+    if is_majority(stats.missing_prices, stats.items_matched) {
+        problems.push(format!(
+            "{} of {} matching listings had no price",
+            stats.missing_prices, stats.items_matched
+        ));
+    }
+
+    problems
+}
+
+/// Whether `part` is more than half of a non-empty `whole`
+fn is_majority(part: u32, whole: u32) -> bool {
+    whole > 0 && u64::from(part) * 2 > u64::from(whole)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counters of a search that fetched one page and parsed the given number of products
+    fn stats(items_parsed: u32) -> ScrapeStats {
+        ScrapeStats {
+            pages_fetched: 1,
+            items_parsed,
+            items_matched: items_parsed,
+            ..ScrapeStats::default()
+        }
+    }
+
+    #[test]
+    fn empty_baseline_only_judges_the_search_itself() {
+        assert!(assess(&stats(3), &[]).is_empty());
+        assert_eq!(assess(&stats(0), &[]), ["Fetched 1 page but found no products on any of them"]);
+    }
+
+    #[test]
+    fn sudden_drop_in_parsed_items_is_reported() {
+        let baseline = [stats(20), stats(22), stats(24)];
+        assert_eq!(assess(&stats(5), &baseline), ["Found 5 products, down from an average of 22"]);
+        assert!(assess(&stats(11), &baseline).is_empty());
+    }
+
+    #[test]
+    fn short_or_small_baseline_does_not_judge_drops() {
+        assert!(assess(&stats(1), &[stats(20), stats(20)]).is_empty());
+        assert!(assess(&stats(1), &[stats(4), stats(4), stats(4)]).is_empty());
+    }
+
+    #[test]
+    fn majority_of_missing_titles_or_prices_is_reported() {
+        let search = ScrapeStats {
+            missing_titles: 6,
+            missing_prices: 3,
+            ..stats(10)
+        };
+        assert_eq!(assess(&search, &[]), ["6 of 10 products had no title"]);
+
+        let search = ScrapeStats {
+            missing_titles: 5,
+            missing_prices: 6,
+            ..stats(10)
+        };
+        assert_eq!(assess(&search, &[]), ["6 of 10 matching listings had no price"]);
+    }
+}
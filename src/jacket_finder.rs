@@ -1,45 +1,58 @@
 //! Core coordination logic for jacket discovery system.
 //! 
-//! Orchestrates scraping, database operations, and Discord notifications.
+//! Orchestrates scraping, database operations, and queueing notifications.
 
-use anyhow::Result;
-use std::sync::Arc;
-use tracing::info;
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{Notify, Semaphore};
+use tracing::{error, info, warn};
 
-use crate::database::Database;
-use crate::discord::DiscordNotifier;
-use crate::scrapers::MarrktScraper;
-use crate::traits::WebsiteScraper;
+use crate::config::env_parse;
+use crate::database::{self, Database, DatabaseConfig, KnownListing};
+use crate::health;
+use crate::models::{
+    Alert, CheckReport, Jacket, ListingAction, ListingOutcome, ListingStatus, NotificationEvent, ScrapeRun, ScrapeStats,
+    ScraperRun, SearchResults,
+};
+use crate::notifiers::{Destination, configured_notifiers, destination_notifiers};
+use crate::outbox::OutboxWorker;
+use crate::scrapers::{MarrktScraper, RateLimiter, definitions_dir, load_definitions};
+use crate::searches::{SavedSearch, load_searches, matching_searches, searches_file};
+use crate::traits::{Notifier, WebsiteScraper};
 
 /// Central coordinator for jacket discovery and notifications
 #[derive(Clone)]
 pub struct JacketFinder {
     scrapers: Vec<Arc<dyn WebsiteScraper>>,
     database: Database,
-    discord: DiscordNotifier,
+    /// Notifiers configured in the environment, used by saved searches without destinations
+    notifiers: Vec<Arc<dyn Notifier>>,
+    /// Notifiers for the destinations of saved searches, by destination key
+    destinations: HashMap<String, Arc<dyn Notifier>>,
+    /// Names of scrapers whose last search failed, so errors are only reported once
+    failing_scrapers: Arc<Mutex<HashSet<String>>>,
+    /// Minimum price drop, in percent, that triggers a notification
+    price_drop_threshold: Decimal,
+    /// Maximum number of websites searched at the same time
+    scrape_concurrency: usize,
+    /// How long one website's search may take before it is abandoned
+    scrape_timeout: Duration,
+    /// Wakes the outbox worker once a check has queued notifications
+    outbox_wake: Arc<Notify>,
 }
 
 impl JacketFinder {
-    /// Create a new jacket finder with default scrapers
+    /// Create a new jacket finder with default scrapers and every definition in the scrapers directory
     /// 
     /// # Returns
     /// * `Result<Self>` - New `JacketFinder` instance or initialization error
     pub async fn new() -> Result<Self> {
-        // Initialize default scrapers
-        let mut scrapers: Vec<Arc<dyn WebsiteScraper>> = Vec::new();
-        
-        // Add Marrkt scraper
-        let marrkt_scraper = MarrktScraper::new()?;
-        scrapers.push(Arc::new(marrkt_scraper));
-        
-        let database = Database::new().await?;
-        let discord = DiscordNotifier::new();
-
-        Ok(Self {
-            scrapers,
-            database,
-            discord,
-        })
+        Self::new_with_scrapers(default_scrapers()?).await
     }
     
     /// Create a new jacket finder with custom scrapers
@@ -49,15 +62,55 @@ impl JacketFinder {
     /// 
     /// # Returns
     /// * `Result<Self>` - New `JacketFinder` instance or initialization error
-    #[allow(dead_code)]
     pub async fn new_with_scrapers(scrapers: Vec<Arc<dyn WebsiteScraper>>) -> Result<Self> {
-        let database = Database::new().await?;
-        let discord = DiscordNotifier::new();
+        let database = database::connect_from_env().await?;
+        if let Some(searches) = load_searches(&searches_file())? {
+            database.sync_saved_searches(&searches).await?;
+        }
+
+        Self::with_database(scrapers, database).await
+    }
+
+    /// Create a jacket finder for dry runs, with the default scrapers and a read-only database
+    /// 
+    /// # Returns
+    /// * `Result<Self>` - New `JacketFinder` instance or initialization error
+    pub async fn new_read_only() -> Result<Self> {
+        let database = database::connect_read_only(&DatabaseConfig::from_env()?).await?;
+        Self::with_database(default_scrapers()?, database).await
+    }
+
+    /// Create a jacket finder around an open database
+    /// 
+    /// # Arguments
+    /// * `scrapers` - Vector of scrapers to use
+    /// * `database` - Database holding listings and saved searches
+    /// 
+    /// # Returns
+    /// * `Result<Self>` - New `JacketFinder` instance or configuration error
+    async fn with_database(scrapers: Vec<Arc<dyn WebsiteScraper>>, database: Database) -> Result<Self> {
+        let notifiers = configured_notifiers()?;
+        let destinations = destination_notifiers(&database.get_saved_searches().await?)?;
+        let price_drop_threshold = price_drop_threshold()?;
+        let scrape_concurrency = env_parse("SCRAPE_CONCURRENCY", DEFAULT_SCRAPE_CONCURRENCY)?;
+        if scrape_concurrency == 0 {
+            bail!("SCRAPE_CONCURRENCY must be at least 1");
+        }
+        let scrape_timeout = env_parse("SCRAPE_TIMEOUT_SECS", DEFAULT_SCRAPE_TIMEOUT_SECS)?;
+        if scrape_timeout == 0 {
+            bail!("SCRAPE_TIMEOUT_SECS must be at least 1");
+        }
 
         Ok(Self {
             scrapers,
             database,
-            discord,
+            notifiers,
+            destinations,
+            failing_scrapers: Arc::new(Mutex::new(HashSet::new())),
+            price_drop_threshold,
+            scrape_concurrency,
+            scrape_timeout: Duration::from_secs(scrape_timeout),
+            outbox_wake: Arc::new(Notify::new()),
         })
     }
     
@@ -70,52 +123,628 @@ impl JacketFinder {
         self.scrapers.push(scraper);
     }
 
+    /// Create the worker delivering the notifications this finder queues
+    /// 
+    /// # Returns
+    /// * `OutboxWorker` - Worker sharing this finder's database and notifiers
+    pub fn outbox_worker(&self) -> OutboxWorker {
+        let mut routes = self.destinations.clone();
+        for notifier in &self.notifiers {
+            routes.insert(notifier.route(), Arc::clone(notifier));
+        }
+
+        OutboxWorker::new(self.database.clone(), routes, Arc::clone(&self.outbox_wake))
+    }
+
+    /// Add a notifier to the list of notification backends
+    /// 
+    /// # Arguments
+    /// * `notifier` - The notifier to add
+    #[allow(dead_code)]
+    pub fn add_notifier(&mut self, notifier: Arc<dyn Notifier>) {
+        self.notifiers.push(notifier);
+    }
 
-    /// Check for new jacket listings across all configured websites and send notifications
+    /// Search every scraper concurrently, reporting scrapers that start failing as error events
+    /// 
+    /// At most `scrape_concurrency` websites are searched at once, and a search taking longer
+    /// than `scrape_timeout` fails. Results are collected in configuration order, however the
+    /// searches interleave. Jackets from a search that only partly failed are kept, but the
+    /// search still counts as failed.
+    /// 
+    /// # Arguments
+    /// * `events` - Events of the current check, extended with any new scraper errors
     /// 
     /// # Returns
-    /// * `Result<()>` - Success or error from scraping, database, or notification operations
-    pub async fn check_for_new_jackets(&self) -> Result<()> {
-        info!("Starting jacket search across {} websites", self.scrapers.len());
-        
-        let existing_ids = self.database.get_existing_jacket_ids().await?;
+    /// * `(Vec<(&Arc<dyn WebsiteScraper>, Jacket)>, Vec<ScraperRun>)` - Every jacket found with the
+    ///   scraper that found it, and how each scraper's search went
+    async fn search_all(
+        &self,
+        events: &mut Vec<NotificationEvent>,
+    ) -> (Vec<(&Arc<dyn WebsiteScraper>, Jacket)>, Vec<ScraperRun>) {
+        let permits = Arc::new(Semaphore::new(self.scrape_concurrency));
+        let started_at = Utc::now();
+        let searches: Vec<_> = self
+            .scrapers
+            .iter()
+            .map(|scraper| tokio::spawn(search_website(Arc::clone(scraper), Arc::clone(&permits), self.scrape_timeout)))
+            .collect();
+
         let mut all_jackets = Vec::new();
+        let mut runs = Vec::new();
 
-        // Search across all configured scrapers
-        for scraper in &self.scrapers {
-            info!("Searching on website: {}", scraper.config().name);
-            
-            match scraper.search_jackets().await {
-                Ok(jackets) => {
-                    info!("Found {} jackets on {}", jackets.len(), scraper.config().name);
-                    all_jackets.extend(jackets);
+        for (scraper, search) in self.scrapers.iter().zip(searches) {
+            let (mut run, result) = match search.await {
+                Ok(outcome) => outcome,
+                Err(e) => (
+                    scraper_run(&scraper.config().name, started_at, ScrapeStats::default()),
+                    Err(anyhow!("Search task failed: {e}")),
+                ),
+            };
+
+            let name = &scraper.config().name;
+            let mut report_failure = |message: String| {
+                if self.failing_scrapers_lock().insert(name.clone()) {
+                    events.push(NotificationEvent::Error {
+                        context: name.clone(),
+                        message,
+                    });
+                }
+            };
+
+            match result {
+                Ok(results) => {
+                    info!("Found {} jackets on {}", results.jackets.len(), name);
+                    all_jackets.extend(results.jackets.into_iter().map(|jacket| (scraper, jacket)));
+
+                    if results.failures.is_empty() {
+                        self.failing_scrapers_lock().remove(name);
+                    } else {
+                        let failures = results.failures.join("; ");
+                        warn!("Search on {} partly failed: {}", name, failures);
+                        report_failure(format!("Search partly failed: {failures}"));
+                        run.error = Some(failures);
+                    }
                 }
                 Err(e) => {
                     // Log error but continue with other scrapers
-                    tracing::error!("Error searching on {}: {}", scraper.config().name, e);
+                    error!("Error searching on {}: {}", name, e);
+                    report_failure(format!("Search failed: {e}"));
+                    run.error = Some(e.to_string());
                 }
             }
+            runs.push(run);
         }
 
-        let mut new_jackets = 0;
+        (all_jackets, runs)
+    }
+
+    /// Check for new jacket listings across all configured websites, saving them and queueing notifications
+    /// 
+    /// # Returns
+    /// * `Result<CheckReport>` - What the check found and did, or an error from scraping or database operations
+    pub async fn check_for_new_jackets(&self) -> Result<CheckReport> {
+        let searches = self.database.get_saved_searches().await?;
+        let report = self.plan_check(&searches, false).await?;
+        self.apply(&report).await?;
+        self.database
+            .record_scrape_run(report.started_at, Utc::now(), &report.scrapers)
+            .await?;
+
+        log_summary(&report);
+        Ok(report)
+    }
 
-        for jacket in all_jackets {
-            if !existing_ids.contains(&jacket.id) {
+    /// Run every scrape and match of a check without saving or notifying anything
+    /// 
+    /// # Returns
+    /// * `Result<CheckReport>` - What a check would save and announce, or an error from scraping or database reads
+    pub async fn dry_run(&self) -> Result<CheckReport> {
+        // The saved searches file is what a real run would sync and use
+        let searches = match load_searches(&searches_file())? {
+            Some(searches) => searches,
+            None => self.database.get_saved_searches().await?,
+        };
+        let report = self.plan_check(&searches, true).await?;
+
+        log_summary(&report);
+        Ok(report)
+    }
+
+    /// Scrape every website and decide what to save and announce, without writing anything
+    /// 
+    /// # Arguments
+    /// * `searches` - Saved searches deciding which listings are announced and where
+    /// * `dry_run` - Whether the report is for a dry run
+    /// 
+    /// # Returns
+    /// * `Result<CheckReport>` - The planned actions or a database error
+    async fn plan_check(&self, searches: &[SavedSearch], dry_run: bool) -> Result<CheckReport> {
+        info!("Starting jacket search across {} websites", self.scrapers.len());
+
+        let started_at = Utc::now();
+        let known_listings = self.database.get_known_listings().await?;
+
+        let mut errors = Vec::new();
+        let (all_jackets, mut scrapers) = self.search_all(&mut errors).await;
+        let history = self.database.recent_scrape_runs(health::HISTORY_RUNS).await?;
+        check_health(&mut scrapers, &history, &mut errors);
+
+        // An unhealthy search may have missed listings that are still there, so it cannot mark any missing
+        let searched_sources: Vec<String> = scrapers
+            .iter()
+            .filter(|run| run.error.is_none() && run.warnings.is_empty())
+            .map(|run| run.scraper.clone())
+            .collect();
+        let alerts = errors
+            .into_iter()
+            .map(|event| Alert {
+                event,
+                recipients: self.default_routes(),
+            })
+            .collect();
+
+        let mut listings = Vec::new();
+        for (scraper, mut jacket) in all_jackets {
+            let action = if let Some(known) = known_listings.get(&jacket.id) {
+                if jacket.status != known.status {
+                    info!("Status of {} changed: {} -> {}", jacket.title, known.status, jacket.status);
+                }
+                sighting_action(known, &jacket, self.price_drop_threshold)
+            } else if jacket.status == ListingStatus::SoldOut {
+                // Tracked so a later restock can be announced, but never notified as new
+                info!("New sold out jacket found: {} - {}", jacket.title, jacket.price);
+                ListingAction::NewSoldOut
+            } else {
                 info!("New jacket found: {} - {}", jacket.title, jacket.price);
 
-                self.database.save_jacket(&jacket).await?;
-                self.discord.send_notification(&jacket).await?;
+                // Detail pages are only fetched for genuinely new listings
+                let mut stats = ScrapeStats::default();
+                if let Err(e) = scraper.enrich_jacket(&mut jacket, &mut stats).await {
+                    warn!("Failed to fetch details for {} on {}: {}", jacket.url, scraper.config().name, e);
+                }
+                if let Some(run) = scrapers.iter_mut().find(|run| run.scraper == scraper.config().name) {
+                    run.stats.pages_disallowed += stats.pages_disallowed;
+                }
+                ListingAction::New
+            };
+
+            // Filters run after enrichment so size filters can use the detail page
+            let recipients = if action.is_notable() {
+                self.recipients(searches, &jacket)
+            } else {
+                Vec::new()
+            };
+            listings.push(ListingOutcome {
+                action,
+                jacket,
+                recipients,
+            });
+        }
+
+        for run in &mut scrapers {
+            let new_items = listings
+                .iter()
+                .filter(|outcome| outcome.jacket.source == run.scraper)
+                .filter(|outcome| matches!(outcome.action, ListingAction::New | ListingAction::NewSoldOut))
+                .count();
+            run.new_items = u32::try_from(new_items).unwrap_or(u32::MAX);
+        }
 
-                new_jackets += 1;
+        // Listings a successful search no longer returns have been removed or sold; sold out
+        // listings keep their status, so a restock is still announced as back in stock
+        let seen: HashSet<&str> = listings.iter().map(|outcome| outcome.jacket.id.as_str()).collect();
+        let mut missing = BTreeMap::new();
+        for (id, known) in &known_listings {
+            if known.status == ListingStatus::Available
+                && searched_sources.contains(&known.source)
+                && !seen.contains(id.as_str())
+            {
+                *missing.entry(known.source.clone()).or_insert(0) += 1;
             }
         }
 
-        if new_jackets > 0 {
-            info!("Found {} new jackets across all websites", new_jackets);
-        } else {
-            info!("No new jackets found across all websites");
+        Ok(CheckReport {
+            started_at,
+            dry_run,
+            searched_sources,
+            listings,
+            alerts,
+            missing,
+            scrapers,
+        })
+    }
+
+    /// Save the listings of a planned check and queue its notifications
+    /// 
+    /// # Arguments
+    /// * `report` - The planned check
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Success or database error
+    async fn apply(&self, report: &CheckReport) -> Result<()> {
+        for alert in &report.alerts {
+            self.database.enqueue(&alert.event, &alert.recipients).await?;
+        }
+
+        // Notifications are queued in the same transaction as the listing change they announce
+        for outcome in &report.listings {
+            let event = outcome.event();
+            match outcome.action {
+                ListingAction::New | ListingAction::NewSoldOut => {
+                    let saved = self
+                        .database
+                        .save_jacket(&outcome.jacket, event.as_ref(), &outcome.recipients)
+                        .await?;
+
+                    // Another instance sharing the database found it first and announces it
+                    if !saved {
+                        info!("{} was already saved by another instance", outcome.jacket.url);
+                        self.database.record_sighting(&outcome.jacket, None, &[]).await?;
+                    }
+                }
+                _ => {
+                    self.database
+                        .record_sighting(&outcome.jacket, event.as_ref(), &outcome.recipients)
+                        .await?;
+                }
+            }
+        }
+
+        // Deliver what this check queued right away instead of waiting for the next poll
+        self.outbox_wake.notify_one();
+
+        for source in &report.searched_sources {
+            let missing = self.database.mark_missing(source, report.started_at).await?;
+            if missing > 0 {
+                info!("{} jackets on {} are no longer listed", missing, source);
+            }
         }
 
         Ok(())
     }
+
+    /// Routing keys of the notifiers configured in the environment
+    fn default_routes(&self) -> Vec<String> {
+        self.notifiers.iter().map(|notifier| notifier.route()).collect()
+    }
+
+    /// Work out where to announce a listing, logging the saved searches it matches
+    /// 
+    /// A listing matching several saved searches that share a destination is routed there once.
+    /// 
+    /// # Arguments
+    /// * `searches` - Saved searches; when there are none, every listing goes to the default notifiers
+    /// * `jacket` - The listing to route
+    /// 
+    /// # Returns
+    /// * `Vec<String>` - Routing keys of the notifiers to queue the listing for, empty if it is unwanted
+    fn recipients(&self, searches: &[SavedSearch], jacket: &Jacket) -> Vec<String> {
+        if searches.is_empty() {
+            return self.default_routes();
+        }
+
+        let matches = matching_searches(searches, jacket);
+        if matches.is_empty() {
+            info!("{} does not match any saved search", jacket.title);
+            return Vec::new();
+        }
+
+        let names: Vec<&str> = matches.iter().map(|search| search.name.as_str()).collect();
+        info!("{} matches saved searches: {}", jacket.title, names.join(", "));
+
+        let mut recipients = Vec::new();
+        for search in matches {
+            let routes = if search.notify.is_empty() {
+                self.default_routes()
+            } else {
+                search.notify.iter().map(Destination::key).collect()
+            };
+            for route in routes {
+                if !recipients.contains(&route) {
+                    recipients.push(route);
+                }
+            }
+        }
+        recipients
+    }
+
+    /// Lock the set of failing scrapers, recovering from a poisoned lock
+    fn failing_scrapers_lock(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.failing_scrapers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Search one website once a concurrency permit is free, abandoning the search after a timeout
+/// 
+/// # Arguments
+/// * `scraper` - The website's scraper
+/// * `permits` - Permits shared by every search of the check
+/// * `timeout` - How long the search may take once it has a permit
+/// 
+/// # Returns
+/// * `(ScraperRun, Result<SearchResults>)` - How the search went, with the jackets found or the error
+async fn search_website(
+    scraper: Arc<dyn WebsiteScraper>,
+    permits: Arc<Semaphore>,
+    timeout: Duration,
+) -> (ScraperRun, Result<SearchResults>) {
+    // The semaphore is never closed, so acquiring only waits for a free permit
+    let _permit = permits.acquire().await.ok();
+    let name = &scraper.config().name;
+    info!("Searching on website: {}", name);
+
+    let started_at = Utc::now();
+    let mut stats = ScrapeStats::default();
+    let result = match tokio::time::timeout(timeout, scraper.search_jackets(&mut stats)).await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow!("Search timed out after {}s", timeout.as_secs())),
+    };
+
+    (scraper_run(name, started_at, stats), result)
+}
+
+/// Record of a search that finished just now, without new items, errors or warnings yet
+/// 
+/// # Arguments
+/// * `scraper` - Name of the scraper
+/// * `started_at` - When the search started
+/// * `stats` - Counters the scraper filled in
+/// 
+/// # Returns
+/// * `ScraperRun` - The record, to be completed once the search's results are known
+fn scraper_run(scraper: &str, started_at: DateTime<Utc>, stats: ScrapeStats) -> ScraperRun {
+    ScraperRun {
+        scraper: scraper.to_string(),
+        started_at,
+        finished_at: Utc::now(),
+        stats,
+        new_items: 0,
+        error: None,
+        warnings: Vec::new(),
+    }
+}
+
+/// Work out what a known listing seen again calls for
+/// 
+/// # Arguments
+/// * `known` - The listing as stored before this check
+/// * `jacket` - The listing as just scraped
+/// * `price_drop_threshold` - Drop in percent a price must exceed to be announced
+/// 
+/// # Returns
+/// * `ListingAction` - Back in stock, a price drop above the threshold, or seen
+fn sighting_action(known: &KnownListing, jacket: &Jacket, price_drop_threshold: Decimal) -> ListingAction {
+    if jacket.status != ListingStatus::Available {
+        return ListingAction::Seen;
+    }
+
+    if known.status == ListingStatus::SoldOut {
+        info!("Jacket back in stock: {} - {}", jacket.title, jacket.price);
+        return ListingAction::BackInStock;
+    }
+
+    let Some(percent) = known.price.drop_percent(&jacket.price) else {
+        return ListingAction::Seen;
+    };
+    if percent <= price_drop_threshold {
+        return ListingAction::Seen;
+    }
+
+    info!(
+        "Price dropped for {}: {} -> {} (-{}%)",
+        jacket.title, known.price, jacket.price, percent
+    );
+    ListingAction::PriceDrop {
+        old_price: known.price.clone(),
+        percent,
+    }
+}
+
+/// Judge the health of every successful search against the scrapers' recorded history
+/// 
+/// Fills in the warnings of each run and reports scrapers that just became unhealthy,
+/// so a broken scraper raises one alert rather than one per check.
+/// 
+/// # Arguments
+/// * `runs` - How each scraper's search went in this check
+/// * `history` - Recently recorded checks, newest first
+/// * `events` - Events of the current check, extended with any newly unhealthy scrapers
+fn check_health(runs: &mut [ScraperRun], history: &[ScrapeRun], events: &mut Vec<NotificationEvent>) {
+    for run in runs.iter_mut().filter(|run| run.error.is_none()) {
+        let earlier: Vec<&ScraperRun> = history
+            .iter()
+            .flat_map(|check| &check.scrapers)
+            .filter(|earlier| earlier.scraper == run.scraper && earlier.error.is_none())
+            .collect();
+        let baseline: Vec<ScrapeStats> = earlier
+            .iter()
+            .filter(|earlier| earlier.warnings.is_empty())
+            .map(|earlier| earlier.stats)
+            .collect();
+
+        run.warnings = health::assess(&run.stats, &baseline);
+        let was_healthy = earlier.first().is_none_or(|previous| previous.warnings.is_empty());
+
+        match (was_healthy, run.warnings.is_empty()) {
+            (true, false) => events.push(NotificationEvent::ScraperUnhealthy {
+                scraper: run.scraper.clone(),
+                problems: run.warnings.clone(),
+            }),
+            (false, true) => info!("{} looks healthy again", run.scraper),
+            _ => {}
+        }
+    }
+}
+
+/// Log how many listings of each kind a check announced
+///
+/// # Arguments
+/// * `report` - The completed or planned check
+fn log_summary(report: &CheckReport) {
+    for run in report.scrapers.iter().filter(|run| !run.warnings.is_empty()) {
+        warn!("{} looks unhealthy: {}", run.scraper, run.warnings.join("; "));
+    }
+
+    let back_in_stock = report.announced("back_in_stock");
+    if back_in_stock > 0 {
+        info!("Found {} jackets back in stock across all websites", back_in_stock);
+    }
+
+    let price_drops = report.announced("price_drop");
+    if price_drops > 0 {
+        info!("Found {} price drops across all websites", price_drops);
+    }
+
+    let new_jackets = report.announced("new");
+    if new_jackets > 0 {
+        info!("Found {} new jackets across all websites", new_jackets);
+    } else {
+        info!("No new jackets found across all websites");
+    }
+}
+
+/// Default minimum price drop, in percent, that triggers a notification
+const DEFAULT_PRICE_DROP_THRESHOLD: &str = "5";
+
+/// Default maximum number of websites searched at the same time
+const DEFAULT_SCRAPE_CONCURRENCY: usize = 4;
+
+/// Default time one website's search may take, in seconds
+const DEFAULT_SCRAPE_TIMEOUT_SECS: u64 = 120;
+
+/// Get the price drop notification threshold from the environment
+///
+/// # Returns
+/// * `Result<Decimal>` - Value of `PRICE_DROP_THRESHOLD_PERCENT`, or 5% when unset
+fn price_drop_threshold() -> Result<Decimal> {
+    let value = std::env::var("PRICE_DROP_THRESHOLD_PERCENT")
+        .unwrap_or_else(|_| DEFAULT_PRICE_DROP_THRESHOLD.to_string());
+
+    Decimal::from_str(value.trim())
+        .with_context(|| format!("Invalid PRICE_DROP_THRESHOLD_PERCENT: {value:?}"))
+}
+
+/// Build the built-in scrapers plus every definition in the scrapers directory
+///
+/// # Returns
+/// * `Result<Vec<Arc<dyn WebsiteScraper>>>` - Scrapers with unique names, or a configuration error
+pub fn default_scrapers() -> Result<Vec<Arc<dyn WebsiteScraper>>> {
+    let mut scrapers: Vec<Arc<dyn WebsiteScraper>> = Vec::new();
+    let limiter = Arc::new(RateLimiter::default());
+
+    // Add Marrkt scraper
+    let marrkt_scraper = MarrktScraper::new(Arc::clone(&limiter))?;
+    scrapers.push(Arc::new(marrkt_scraper));
+
+    // Add declarative scrapers from TOML definitions
+    scrapers.extend(load_definitions(&definitions_dir(), &limiter)?);
+    ensure_unique_names(&scrapers)?;
+
+    Ok(scrapers)
+}
+
+/// Reject scraper sets where two scrapers share a name, since names are part of listing IDs
+///
+/// # Arguments
+/// * `scrapers` - Scrapers to check
+///
+/// # Returns
+/// * `Result<()>` - Success or an error naming the duplicated scraper
+fn ensure_unique_names(scrapers: &[Arc<dyn WebsiteScraper>]) -> Result<()> {
+    let mut names = HashSet::new();
+    for scraper in scrapers {
+        let name = &scraper.config().name;
+        if !names.insert(name.to_lowercase()) {
+            bail!("Duplicate scraper name '{name}' - scraper names must be unique");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discord::DiscordNotifier;
+    use crate::models::Price;
+    use chrono::Duration as ChronoDuration;
+
+    /// Listing of the test website with the given status
+    fn jacket(id: &str, status: ListingStatus) -> Jacket {
+        Jacket {
+            id: id.to_string(),
+            source: "Test".to_string(),
+            title: "Barbour Bedale".to_string(),
+            brand: None,
+            price: Price::parse("€ 250,00"),
+            url: format!("https://example.com/{id}"),
+            image_url: None,
+            discovered_at: Utc::now(),
+            size: None,
+            measurements: None,
+            condition: None,
+            description: None,
+            status,
+        }
+    }
+
+    #[tokio::test]
+    async fn sold_out_listing_that_disappears_is_back_in_stock_when_it_returns() {
+        let db = database::connect(&DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            ..DatabaseConfig::default()
+        })
+        .await
+        .unwrap();
+        db.save_jacket(&jacket("sold", ListingStatus::SoldOut), None, &[]).await.unwrap();
+        db.save_jacket(&jacket("listed", ListingStatus::Available), None, &[]).await.unwrap();
+
+        let marked = db.mark_missing("Test", Utc::now() + ChronoDuration::minutes(1)).await.unwrap();
+        assert_eq!(marked, 1);
+
+        let known = db.get_known_listings().await.unwrap();
+        assert_eq!(known["listed"].status, ListingStatus::Missing);
+        assert_eq!(known["sold"].status, ListingStatus::SoldOut);
+
+        let action = sighting_action(&known["sold"], &jacket("sold", ListingStatus::Available), Decimal::from(10));
+        assert!(matches!(action, ListingAction::BackInStock));
+    }
+
+    #[tokio::test]
+    async fn overlapping_searches_route_a_shared_destination_once() {
+        let webhook = "https://discord.com/api/webhooks/1/token";
+        let database = database::connect(&DatabaseConfig {
+            url: "sqlite::memory:".to_string(),
+            ..DatabaseConfig::default()
+        })
+        .await
+        .unwrap();
+        let finder = JacketFinder {
+            scrapers: Vec::new(),
+            database,
+            notifiers: vec![Arc::new(DiscordNotifier::new(webhook.to_string()))],
+            destinations: HashMap::new(),
+            failing_scrapers: Arc::new(Mutex::new(HashSet::new())),
+            price_drop_threshold: Decimal::from(10),
+            scrape_concurrency: 1,
+            scrape_timeout: Duration::from_secs(1),
+            outbox_wake: Arc::new(Notify::new()),
+        };
+        let searches = [
+            SavedSearch {
+                name: "Defaults".to_string(),
+                ..SavedSearch::default()
+            },
+            SavedSearch {
+                name: "Same webhook".to_string(),
+                notify: vec![Destination::Discord(webhook.to_string())],
+                ..SavedSearch::default()
+            },
+        ];
+
+        let recipients = finder.recipients(&searches, &jacket("listed", ListingStatus::Available));
+        assert_eq!(recipients, vec![Destination::Discord(webhook.to_string()).key()]);
+    }
 }
@@ -2,16 +2,27 @@
 //!
 //! Monitors Marrkt.com for new N-1 deck jacket listings and sends Discord notifications.
 //! Runs every 5 minutes and stores results in `SQLite` to prevent duplicate notifications.
+//! Subcommands run a single check, inspect stored listings and test scrapers and notifiers.
 
 use anyhow::Result;
+use clap::Parser;
 use tokio_cron_scheduler::{Job, JobScheduler};
 use tracing::{error, info};
 
+mod cli;
+mod config;
 mod database;
 mod discord;
+mod email;
+mod health;
 mod jacket_finder;
 mod models;
+mod notifiers;
+mod outbox;
+mod query;
 mod scrapers;
+mod searches;
+mod telegram;
 mod traits;
 
 /// Application entry point and runtime management
@@ -49,6 +60,9 @@ impl App {
 
     /// Run the application
     async fn run(&self) -> Result<()> {
+        // Deliver queued notifications, including any left over from a previous run
+        tokio::spawn(self.finder.outbox_worker().run());
+
         // Initial check to populate database
         info!("Running initial jacket check");
         if let Err(e) = self.finder.check_for_new_jackets().await {
@@ -67,15 +81,30 @@ impl App {
     }
 }
 
+use cli::{Cli, Command, DbCommand};
 use jacket_finder::JacketFinder;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     dotenvy::dotenv().ok();
-    tracing_subscriber::fmt::init();
+    // Logs go to stderr so command output can be piped
+    tracing_subscriber::fmt().with_writer(std::io::stderr).init();
+
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => {
+            info!("Starting N-1 Deck Jacket Finder Bot");
 
-    info!("Starting N-1 Deck Jacket Finder Bot");
-    
-    let app = App::new().await?;
-    app.run().await
+            let app = App::new().await?;
+            app.run().await
+        }
+        Command::Once(args) => cli::once(args).await,
+        Command::List(args) => cli::list(args).await,
+        Command::TestScraper(args) => cli::test_scraper(args).await,
+        Command::TestNotify(args) => cli::test_notify(args).await,
+        Command::Runs(args) => cli::runs(args).await,
+        Command::Db(DbCommand::Migrate) => cli::db_migrate().await,
+        Command::Db(DbCommand::Stats) => cli::db_stats().await,
+    }
 }
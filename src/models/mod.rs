@@ -1,17 +1,293 @@
-//! Data models for jacket information and Discord webhook payloads
+//! Data models for jacket information and notification payloads
 
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-/// A jacket listing scraped from Marrkt
+mod price;
+
+pub use price::Price;
+
+/// A jacket listing scraped from a website
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Jacket {
     pub id: String,
+    /// Name of the scraper that found the listing
+    pub source: String,
     pub title: String,
-    pub price: String,
+    /// Brand as reported by the website, when it lists one separately
+    pub brand: Option<String>,
+    pub price: Price,
     pub url: String,
     pub image_url: Option<String>,
     pub discovered_at: DateTime<Utc>,
+    pub size: Option<String>,
+    pub measurements: Option<String>,
+    pub condition: Option<String>,
+    pub description: Option<String>,
+    pub status: ListingStatus,
+}
+
+/// Availability of a listing on its website
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingStatus {
+    /// Listed and purchasable
+    Available,
+    /// Listed but marked as sold out
+    SoldOut,
+    /// Was available but is no longer returned by a successful search of its website
+    Missing,
+}
+
+impl ListingStatus {
+    /// Get the value stored in the database for this status
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Available => "available",
+            Self::SoldOut => "sold_out",
+            Self::Missing => "missing",
+        }
+    }
+
+    /// Parse a status stored in the database, treating unknown values as available
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "sold_out" => Self::SoldOut,
+            "missing" => Self::Missing,
+            _ => Self::Available,
+        }
+    }
+}
+
+impl std::fmt::Display for ListingStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A known listing that became cheaper since it was last observed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceDrop {
+    /// The listing with its new price
+    pub jacket: Jacket,
+    /// Price before the drop
+    pub old_price: Price,
+    /// Drop relative to the old price, in percent
+    pub percent: Decimal,
+}
+
+/// Something worth telling the user about
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// A listing seen for the first time
+    NewListing(Jacket),
+    /// A known listing became cheaper
+    PriceDrop(PriceDrop),
+    /// A sold out listing is available again
+    BackInStock(Jacket),
+    /// Something went wrong while checking for jackets
+    Error {
+        /// What failed, e.g. the scraper name
+        context: String,
+        /// Error description
+        message: String,
+    },
+    /// A scraper's search succeeded but its results look wrong, e.g. after the website changed its markup
+    ScraperUnhealthy {
+        /// Name of the scraper
+        scraper: String,
+        /// What looks wrong, one sentence each
+        problems: Vec<String>,
+    },
+}
+
+impl NotificationEvent {
+    /// ID of the listing the event is about, if any
+    pub fn jacket_id(&self) -> Option<&str> {
+        match self {
+            Self::NewListing(jacket) | Self::BackInStock(jacket) => Some(&jacket.id),
+            Self::PriceDrop(drop) => Some(&drop.jacket.id),
+            Self::Error { .. } | Self::ScraperUnhealthy { .. } => None,
+        }
+    }
+}
+
+/// What a check does about one listing it found
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ListingAction {
+    /// Saved as a new listing
+    New,
+    /// Saved as a new listing without announcing it, since it is already sold out
+    NewSoldOut,
+    /// A known listing seen again without notable changes
+    Seen,
+    /// A known listing became cheaper by more than the threshold
+    PriceDrop {
+        /// Price before the drop
+        old_price: Price,
+        /// Drop relative to the old price, in percent
+        percent: Decimal,
+    },
+    /// A known sold out listing is available again
+    BackInStock,
+}
+
+impl ListingAction {
+    /// Short name of the action, as used in reports
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::NewSoldOut => "new_sold_out",
+            Self::Seen => "seen",
+            Self::PriceDrop { .. } => "price_drop",
+            Self::BackInStock => "back_in_stock",
+        }
+    }
+
+    /// Whether the action is worth a notification when a saved search wants the listing
+    pub fn is_notable(&self) -> bool {
+        matches!(self, Self::New | Self::PriceDrop { .. } | Self::BackInStock)
+    }
+}
+
+/// A listing found by a check and what the check does about it
+#[derive(Debug, Clone, Serialize)]
+pub struct ListingOutcome {
+    #[serde(flatten)]
+    pub action: ListingAction,
+    pub jacket: Jacket,
+    /// Routing keys of the notifiers the listing is announced to, empty if it is not announced
+    pub recipients: Vec<String>,
+}
+
+impl ListingOutcome {
+    /// Build the notification announcing this outcome
+    ///
+    /// # Returns
+    /// * `Option<NotificationEvent>` - The event, or `None` if the outcome is not announced
+    pub fn event(&self) -> Option<NotificationEvent> {
+        if self.recipients.is_empty() {
+            return None;
+        }
+
+        match &self.action {
+            ListingAction::New => Some(NotificationEvent::NewListing(self.jacket.clone())),
+            ListingAction::BackInStock => Some(NotificationEvent::BackInStock(self.jacket.clone())),
+            ListingAction::PriceDrop { old_price, percent } => Some(NotificationEvent::PriceDrop(PriceDrop {
+                jacket: self.jacket.clone(),
+                old_price: old_price.clone(),
+                percent: *percent,
+            })),
+            ListingAction::NewSoldOut | ListingAction::Seen => None,
+        }
+    }
+}
+
+/// A notification not tied to a listing, such as a scraper error
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub event: NotificationEvent,
+    /// Routing keys of the notifiers the alert is sent to
+    pub recipients: Vec<String>,
+}
+
+/// Everything one check found and what it saves and announces
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckReport {
+    pub started_at: DateTime<Utc>,
+    /// Whether the check only reported its actions without saving or notifying anything
+    pub dry_run: bool,
+    /// Names of the scrapers whose search succeeded
+    pub searched_sources: Vec<String>,
+    /// Every listing found, in the order the scrapers returned them
+    pub listings: Vec<ListingOutcome>,
+    pub alerts: Vec<Alert>,
+    /// Number of known listings per website that its search no longer returns
+    pub missing: BTreeMap<String, u64>,
+    /// How each scraper's search went, in configuration order
+    pub scrapers: Vec<ScraperRun>,
+}
+
+impl CheckReport {
+    /// Count the announced listings with a given action
+    ///
+    /// # Arguments
+    /// * `action` - Action name, see [`ListingAction::as_str`]
+    ///
+    /// # Returns
+    /// * `usize` - Number of listings with that action and at least one recipient
+    pub fn announced(&self, action: &str) -> usize {
+        self.listings
+            .iter()
+            .filter(|outcome| outcome.action.as_str() == action && !outcome.recipients.is_empty())
+            .count()
+    }
+
+    /// Count the notifications the check queues, one per recipient
+    pub fn notification_count(&self) -> usize {
+        self.listings
+            .iter()
+            .filter(|outcome| outcome.action.is_notable())
+            .map(|outcome| outcome.recipients.len())
+            .chain(self.alerts.iter().map(|alert| alert.recipients.len()))
+            .sum()
+    }
+}
+
+/// Counters a scraper fills in while searching its website
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ScrapeStats {
+    /// Search result pages fetched successfully
+    pub pages_fetched: u32,
+    /// Product entries found on those pages, before the title filter
+    pub items_parsed: u32,
+    /// Distinct listings matching the title filter
+    pub items_matched: u32,
+    /// Products whose title could not be found, before the title filter
+    pub missing_titles: u32,
+    /// Matching listings whose price could not be found or parsed
+    pub missing_prices: u32,
+    /// Search result and detail pages skipped because the website's robots.txt disallows them
+    pub pages_disallowed: u32,
+}
+
+/// Listings a scraper's search found, possibly despite some pages failing
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    pub jackets: Vec<Jacket>,
+    /// Why some pages or search terms could not be searched; the jackets come from the rest
+    pub failures: Vec<String>,
+}
+
+/// How one scraper's search went during a check
+#[derive(Debug, Clone, Serialize)]
+pub struct ScraperRun {
+    /// Name of the scraper
+    pub scraper: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub stats: ScrapeStats,
+    /// Listings the check saw for the first time
+    pub new_items: u32,
+    /// Why the search failed, if it did; listings from pages that did not fail are still used
+    pub error: Option<String>,
+    /// Why a successful search looks wrong, empty if it looks healthy
+    pub warnings: Vec<String>,
+}
+
+/// A recorded check and the searches it ran
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrapeRun {
+    pub id: i64,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    /// One entry per scraper, in configuration order
+    pub scrapers: Vec<ScraperRun>,
 }
 
 /// Discord embed structure for rich notifications
@@ -19,7 +295,8 @@ pub struct Jacket {
 pub struct DiscordEmbed {
     pub title: String,
     pub description: String,
-    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
     pub color: u32,
     pub timestamp: String,
     pub thumbnail: Option<DiscordThumbnail>,
@@ -50,5 +327,49 @@ pub struct DiscordField {
 /// Discord webhook message payload
 #[derive(Debug, Serialize)]
 pub struct DiscordMessage {
+    /// Plain text shown above the embeds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
     pub embeds: Vec<DiscordEmbed>,
 }
+
+/// Telegram Bot API `sendMessage` payload
+#[derive(Debug, Serialize)]
+pub struct TelegramMessage {
+    pub chat_id: String,
+    pub text: String,
+    pub parse_mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_markup: Option<TelegramReplyMarkup>,
+}
+
+/// Telegram Bot API `sendPhoto` payload
+#[derive(Debug, Serialize)]
+pub struct TelegramPhoto {
+    pub chat_id: String,
+    pub photo: String,
+    pub caption: String,
+    pub parse_mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_markup: Option<TelegramReplyMarkup>,
+}
+
+/// Inline keyboard attached to a Telegram message
+#[derive(Debug, Clone, Serialize)]
+pub struct TelegramReplyMarkup {
+    pub inline_keyboard: Vec<Vec<TelegramInlineButton>>,
+}
+
+/// Inline keyboard button opening a URL
+#[derive(Debug, Clone, Serialize)]
+pub struct TelegramInlineButton {
+    pub text: String,
+    pub url: String,
+}
+
+/// Telegram Bot API response envelope
+#[derive(Debug, Deserialize)]
+pub struct TelegramResponse {
+    pub ok: bool,
+    pub description: Option<String>,
+}
@@ -0,0 +1,276 @@
+//! Structured listing prices parsed from free-form text
+
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// ISO 4217 codes recognized when they appear in price text
+const CURRENCY_CODES: &[&str] = &[
+    "EUR", "USD", "GBP", "JPY", "CHF", "SEK", "DKK", "NOK", "CAD", "AUD", "PLN", "CZK",
+];
+
+/// Currency symbols and the ISO 4217 codes they stand for, most specific first
+const CURRENCY_SYMBOLS: &[(&str, &str)] = &[
+    ("US$", "USD"),
+    ("CA$", "CAD"),
+    ("AU$", "AUD"),
+    ("C$", "CAD"),
+    ("A$", "AUD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("$", "USD"),
+];
+
+/// Number of decimal places kept when storing amounts as integer minor units
+const MINOR_UNIT_SCALE: u32 = 2;
+
+/// A listing price with its parsed amount, currency and original text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Price {
+    /// Parsed amount, `None` when the text could not be parsed
+    pub amount: Option<Decimal>,
+    /// ISO 4217 currency code, `None` when no currency was recognized
+    pub currency: Option<String>,
+    /// Original price text as shown on the website
+    pub raw: String,
+}
+
+impl Price {
+    /// Parse a price from free-form text such as `€ 1.249,00`, `$1,249.99` or `249,00 EUR`
+    ///
+    /// Parsing never fails: when no amount can be found the raw text is kept and
+    /// `amount` is `None`.
+    ///
+    /// # Arguments
+    /// * `raw` - Price text as shown on the website
+    ///
+    /// # Returns
+    /// * `Self` - The parsed price
+    pub fn parse(raw: &str) -> Self {
+        let raw = raw.trim();
+
+        Self {
+            amount: parse_amount(raw),
+            currency: parse_currency(raw),
+            raw: raw.to_string(),
+        }
+    }
+
+    /// Rebuild a price from its stored database columns
+    ///
+    /// # Arguments
+    /// * `raw` - Original price text
+    /// * `amount_minor` - Amount in minor units (e.g. cents), if parsed
+    /// * `currency` - ISO 4217 currency code, if recognized
+    ///
+    /// # Returns
+    /// * `Self` - The stored price
+    pub fn from_parts(raw: String, amount_minor: Option<i64>, currency: Option<String>) -> Self {
+        Self {
+            amount: amount_minor.map(|minor| Decimal::new(minor, MINOR_UNIT_SCALE)),
+            currency,
+            raw,
+        }
+    }
+
+    /// Get the amount in minor units (e.g. cents) for storage
+    ///
+    /// # Returns
+    /// * `Option<i64>` - Amount times 100, rounded, or `None` if unparsed or out of range
+    pub fn amount_minor(&self) -> Option<i64> {
+        self.amount
+            .and_then(|amount| amount.checked_mul(Decimal::from(10_i64.pow(MINOR_UNIT_SCALE))))
+            .and_then(|minor| minor.round().to_i64())
+    }
+
+    /// Compute how much cheaper `new` is than this price, in percent
+    ///
+    /// # Arguments
+    /// * `new` - The newly observed price
+    ///
+    /// # Returns
+    /// * `Option<Decimal>` - Drop percentage rounded to one decimal, or `None` if the price did not
+    ///   drop, either amount is unknown, or the currencies differ
+    pub fn drop_percent(&self, new: &Self) -> Option<Decimal> {
+        let (old_amount, new_amount) = (self.amount?, new.amount?);
+        if self.currency.is_some() && new.currency.is_some() && self.currency != new.currency {
+            return None;
+        }
+        if old_amount <= Decimal::ZERO || new_amount >= old_amount {
+            return None;
+        }
+
+        let percent = (old_amount - new_amount) / old_amount * Decimal::ONE_HUNDRED;
+        Some(percent.round_dp(1))
+    }
+}
+
+impl fmt::Display for Price {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.amount, &self.currency) {
+            _ if !self.raw.is_empty() => f.write_str(&self.raw),
+            (Some(amount), Some(currency)) => write!(f, "{amount} {currency}"),
+            (Some(amount), None) => write!(f, "{amount}"),
+            (None, _) => f.write_str("Price not found"),
+        }
+    }
+}
+
+/// Find the currency in price text, preferring explicit ISO codes over symbols
+fn parse_currency(raw: &str) -> Option<String> {
+    let code = raw
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .map(str::to_ascii_uppercase)
+        .find(|word| CURRENCY_CODES.contains(&word.as_str()));
+
+    code.or_else(|| {
+        CURRENCY_SYMBOLS
+            .iter()
+            .find(|(symbol, _)| raw.contains(symbol))
+            .map(|(_, code)| (*code).to_string())
+    })
+}
+
+/// Extract the numeric amount from price text
+///
+/// Handles comma or dot decimals and dot, comma, space or apostrophe thousand separators.
+/// A single separator followed by exactly three digits is read as a thousand separator.
+fn parse_amount(raw: &str) -> Option<Decimal> {
+    let start = raw.find(|c: char| c.is_ascii_digit())?;
+    let number: String = raw[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '\'' | ' ' | '\u{a0}' | '\u{202f}'))
+        .filter(|c| !matches!(c, '\'' | ' ' | '\u{a0}' | '\u{202f}'))
+        .collect();
+    let number = number.trim_end_matches(['.', ',']);
+
+    let decimal_separator = match (number.rfind('.'), number.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(if dot > comma { '.' } else { ',' }),
+        (Some(pos), None) | (None, Some(pos)) => {
+            let separator = if number[pos..].starts_with('.') { '.' } else { ',' };
+            let occurrences = number.matches(separator).count();
+            let digits_after = number.len() - pos - 1;
+            (occurrences == 1 && digits_after != 3).then_some(separator)
+        }
+        (None, None) => None,
+    };
+
+    let normalized: String = number
+        .chars()
+        .filter_map(|c| match c {
+            '.' | ',' if Some(c) == decimal_separator => Some('.'),
+            '.' | ',' => None,
+            digit => Some(digit),
+        })
+        .collect();
+
+    Decimal::from_str(&normalized).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_comma_decimals_with_dot_thousands() {
+        let price = Price::parse("€1.234,56");
+        assert_eq!(price.amount, Some(Decimal::new(123_456, 2)));
+        assert_eq!(price.currency.as_deref(), Some("EUR"));
+        assert_eq!(price.raw, "€1.234,56");
+    }
+
+    #[test]
+    fn parses_dot_decimals_with_comma_thousands() {
+        let price = Price::parse("1,234.56");
+        assert_eq!(price.amount, Some(Decimal::new(123_456, 2)));
+        assert_eq!(price.currency, None);
+    }
+
+    #[test]
+    fn reads_a_single_separator_before_three_digits_as_thousands() {
+        assert_eq!(Price::parse("€ 1.249").amount, Some(Decimal::from(1249)));
+        assert_eq!(Price::parse("$1,249").amount, Some(Decimal::from(1249)));
+        assert_eq!(Price::parse("249,00").amount, Some(Decimal::new(24_900, 2)));
+        assert_eq!(Price::parse("CHF 1'249.50").amount, Some(Decimal::new(124_950, 2)));
+    }
+
+    #[test]
+    fn finds_currency_symbols_before_and_after_the_amount() {
+        for (raw, amount, currency) in [
+            ("€ 349,00", Decimal::new(34_900, 2), "EUR"),
+            ("349,00 €", Decimal::new(34_900, 2), "EUR"),
+            ("£95", Decimal::from(95), "GBP"),
+            ("95£", Decimal::from(95), "GBP"),
+            ("$1,249.99", Decimal::new(124_999, 2), "USD"),
+            ("1,249.99 US$", Decimal::new(124_999, 2), "USD"),
+            ("C$ 400", Decimal::from(400), "CAD"),
+        ] {
+            let price = Price::parse(raw);
+            assert_eq!(price.amount, Some(amount), "amount of {raw:?}");
+            assert_eq!(price.currency.as_deref(), Some(currency), "currency of {raw:?}");
+        }
+    }
+
+    #[test]
+    fn prefers_iso_codes_over_symbols() {
+        let price = Price::parse("$ 300 CAD");
+        assert_eq!(price.currency.as_deref(), Some("CAD"));
+        assert_eq!(Price::parse("249,00 eur").currency.as_deref(), Some("EUR"));
+    }
+
+    #[test]
+    fn keeps_unparseable_text_without_an_amount() {
+        let price = Price::parse("  Price on request ");
+        assert_eq!(price.amount, None);
+        assert_eq!(price.amount_minor(), None);
+        assert_eq!(price.raw, "Price on request");
+        assert_eq!(price.to_string(), "Price on request");
+    }
+
+    #[test]
+    fn converts_to_and_from_minor_units() {
+        let price = Price::parse("€1.234,56");
+        assert_eq!(price.amount_minor(), Some(123_456));
+        assert_eq!(Price::parse("€ 1.249").amount_minor(), Some(124_900));
+
+        let stored = Price::from_parts(price.raw.clone(), price.amount_minor(), price.currency.clone());
+        assert_eq!(stored, price);
+    }
+
+    #[test]
+    fn computes_drop_percent_rounded_to_one_decimal() {
+        let old = Price::parse("€ 300,00");
+        assert_eq!(old.drop_percent(&Price::parse("€ 200,00")), Some(Decimal::new(333, 1)));
+        assert_eq!(old.drop_percent(&Price::parse("€ 150")), Some(Decimal::new(500, 1)));
+    }
+
+    #[test]
+    fn reports_no_drop_for_equal_or_higher_prices() {
+        let old = Price::parse("€ 300,00");
+        assert_eq!(old.drop_percent(&Price::parse("€ 300,00")), None);
+        assert_eq!(old.drop_percent(&Price::parse("€ 350,00")), None);
+    }
+
+    #[test]
+    fn reports_no_drop_across_currencies() {
+        let old = Price::parse("€ 300,00");
+        assert_eq!(old.drop_percent(&Price::parse("$200.00")), None);
+        assert_eq!(old.drop_percent(&Price::parse("200 GBP")), None);
+    }
+
+    #[test]
+    fn compares_amounts_when_one_currency_is_unknown() {
+        let old = Price::parse("€ 300,00");
+        assert_eq!(old.drop_percent(&Price::parse("200,00")), Some(Decimal::new(333, 1)));
+    }
+
+    #[test]
+    fn reports_no_drop_without_amounts() {
+        let old = Price::parse("€ 300,00");
+        assert_eq!(old.drop_percent(&Price::parse("Sold")), None);
+        assert_eq!(Price::parse("Sold").drop_percent(&old), None);
+    }
+}
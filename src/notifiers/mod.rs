@@ -0,0 +1,193 @@
+//! Construction of the notification backends enabled by configuration
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::discord::DiscordNotifier;
+use crate::email::EmailNotifier;
+use crate::models::NotificationEvent;
+use crate::searches::SavedSearch;
+use crate::telegram::TelegramNotifier;
+use crate::traits::Notifier;
+
+/// Build every notifier configured in the environment
+///
+/// # Returns
+/// * `Result<Vec<Arc<dyn Notifier>>>` - Enabled notifiers, possibly empty, or a configuration error
+pub fn configured_notifiers() -> Result<Vec<Arc<dyn Notifier>>> {
+    let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+
+    if let Some(discord) = DiscordNotifier::from_env()? {
+        notifiers.push(Arc::new(discord));
+    }
+
+    if let Some(telegram) = TelegramNotifier::from_env() {
+        notifiers.push(Arc::new(telegram));
+    }
+
+    if let Some(email) = EmailNotifier::from_env()? {
+        notifiers.push(Arc::new(email));
+    }
+
+    if notifiers.is_empty() {
+        warn!("No notifiers configured - jackets will be recorded without notifications");
+    }
+
+    Ok(notifiers)
+}
+
+/// Where a saved search sends its notifications
+///
+/// Written in TOML as `{ discord = "https://..." }`, `{ telegram = "-1001234" }` or
+/// `{ email = "alice@example.com" }`. Bot tokens and SMTP servers still come from the environment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Destination {
+    /// Discord webhook URL
+    Discord(String),
+    /// Telegram chat ID or `@channel` username
+    Telegram(String),
+    /// Email recipient address
+    Email(String),
+}
+
+impl Destination {
+    /// Key identifying this destination in the notification outbox
+    ///
+    /// Webhook URLs contain a secret token, so Discord destinations are keyed by a hash of the URL.
+    ///
+    /// # Returns
+    /// * `String` - Key such as `telegram:-1001234`, stable across restarts
+    pub fn key(&self) -> String {
+        match self {
+            Self::Discord(url) => format!("discord:{}", &format!("{:x}", md5::compute(url.trim()))[..12]),
+            Self::Telegram(chat_id) => format!("telegram:{}", chat_id.trim()),
+            Self::Email(address) => format!("email:{}", address.trim().to_lowercase()),
+        }
+    }
+
+    /// Build a notifier delivering to this destination
+    ///
+    /// # Returns
+    /// * `Result<Arc<dyn Notifier>>` - The notifier, or an error if the destination is invalid or
+    ///   the environment lacks the settings its backend needs
+    pub fn build(&self) -> Result<Arc<dyn Notifier>> {
+        let notifier: Arc<dyn Notifier> = match self {
+            Self::Discord(url) => {
+                if !url.trim().starts_with("http") {
+                    bail!("Discord destination {url:?} is not a webhook URL");
+                }
+                Arc::new(DiscordNotifier::for_webhook(url.trim().to_string())?)
+            }
+            Self::Telegram(chat_id) => {
+                if chat_id.trim().is_empty() {
+                    bail!("Telegram destination must not be empty");
+                }
+                Arc::new(TelegramNotifier::for_chat(chat_id.trim())?)
+            }
+            Self::Email(address) => Arc::new(EmailNotifier::for_address(address.trim())?),
+        };
+        Ok(notifier)
+    }
+}
+
+impl fmt::Display for Destination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The key hides webhook tokens, which must not end up in logs
+        f.write_str(&self.key())
+    }
+}
+
+/// Build a notifier for every distinct destination of a set of saved searches
+///
+/// # Arguments
+/// * `searches` - Saved searches whose `notify` destinations to build
+///
+/// # Returns
+/// * `Result<HashMap<String, Arc<dyn Notifier>>>` - Notifiers by destination key, or an error
+///   naming the saved search with an unusable destination
+pub fn destination_notifiers(searches: &[SavedSearch]) -> Result<HashMap<String, Arc<dyn Notifier>>> {
+    let mut notifiers = HashMap::new();
+
+    for search in searches {
+        for destination in &search.notify {
+            if notifiers.contains_key(&destination.key()) {
+                continue;
+            }
+            let notifier = destination
+                .build()
+                .with_context(|| format!("Invalid destination {} in saved search '{}'", destination, search.name))?;
+            notifiers.insert(destination.key(), notifier);
+        }
+    }
+
+    Ok(notifiers)
+}
+
+/// Summarize a batch of events by kind, e.g. "2 new jackets, 1 price drop"
+///
+/// # Arguments
+/// * `events` - The events to summarize
+///
+/// # Returns
+/// * `String` - Counts of each kind of event present, separated by commas
+pub fn summarize(events: &[NotificationEvent]) -> String {
+    let count = |kind: fn(&NotificationEvent) -> bool| events.iter().filter(|event| kind(event)).count();
+
+    let mut parts = Vec::new();
+    for (count, singular, plural) in [
+        (count(|event| matches!(event, NotificationEvent::NewListing(_))), "new jacket", "new jackets"),
+        (count(|event| matches!(event, NotificationEvent::PriceDrop(_))), "price drop", "price drops"),
+        (count(|event| matches!(event, NotificationEvent::BackInStock(_))), "back in stock", "back in stock"),
+        (count(|event| matches!(event, NotificationEvent::Error { .. })), "error", "errors"),
+        (
+            count(|event| matches!(event, NotificationEvent::ScraperUnhealthy { .. })),
+            "unhealthy scraper",
+            "unhealthy scrapers",
+        ),
+    ] {
+        match count {
+            0 => {}
+            1 => parts.push(format!("1 {singular}")),
+            _ => parts.push(format!("{count} {plural}")),
+        }
+    }
+
+    parts.join(", ")
+}
+
+/// Shorten text to at most `limit` characters, marking the cut with an ellipsis
+///
+/// # Arguments
+/// * `text` - Text to shorten
+/// * `limit` - Maximum number of characters, including the ellipsis
+///
+/// # Returns
+/// * `String` - The text, truncated if it was too long
+pub fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+
+    let mut truncated: String = text.chars().take(limit.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Escape text for inclusion in HTML, including Telegram's HTML parse mode
+///
+/// # Arguments
+/// * `text` - Text to escape
+///
+/// # Returns
+/// * `String` - Text with `&`, `<`, `>` and `"` replaced by entities
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,229 @@
+//! Background delivery of queued notifications with retries and dead letters
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{error, info, warn};
+
+use crate::database::{Database, OutboxEntry};
+use crate::traits::Notifier;
+
+/// How often the outbox is checked for due entries when nobody wakes the worker
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Number of failed attempts after which an entry becomes a dead letter
+const MAX_ATTEMPTS: u32 = 8;
+
+/// Delay before the first retry, doubled after every further failure
+const BASE_RETRY_DELAY: chrono::Duration = chrono::Duration::minutes(1);
+
+/// Longest delay between two attempts
+const MAX_RETRY_DELAY: chrono::Duration = chrono::Duration::hours(1);
+
+/// How long claimed entries are reserved for this worker before another worker may retry them
+const CLAIM_LEASE: chrono::Duration = chrono::Duration::minutes(15);
+
+/// Worker delivering queued notifications to their notifiers
+pub struct OutboxWorker {
+    database: Database,
+    /// Notifiers by the routing key stored with each entry
+    routes: HashMap<String, Arc<dyn Notifier>>,
+    wake: Arc<Notify>,
+}
+
+impl OutboxWorker {
+    /// Create a new outbox worker
+    ///
+    /// # Arguments
+    /// * `database` - Database holding the outbox
+    /// * `routes` - Notifiers to deliver entries with, by the routing key stored with each entry
+    /// * `wake` - Signal used to start a delivery round without waiting for the next poll
+    ///
+    /// # Returns
+    /// * `Self` - New `OutboxWorker` instance
+    pub fn new(database: Database, routes: HashMap<String, Arc<dyn Notifier>>, wake: Arc<Notify>) -> Self {
+        Self {
+            database,
+            routes,
+            wake,
+        }
+    }
+
+    /// Deliver due entries forever, whenever woken and at least every `POLL_INTERVAL`
+    pub async fn run(self) {
+        loop {
+            if let Err(e) = self.drain().await {
+                error!("Failed to process notification outbox: {}", e);
+            }
+
+            tokio::select! {
+                () = self.wake.notified() => {}
+                () = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+        }
+    }
+
+    /// Attempt delivery of every due entry, batching them per notifier
+    ///
+    /// Entries are claimed first, so workers of other instances sharing the database skip them.
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success, or a database error; delivery failures are recorded, not returned
+    pub async fn drain(&self) -> Result<()> {
+        let now = Utc::now();
+        let entries = self.database.claim_outbox_entries(now, now + CLAIM_LEASE).await?;
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut by_notifier: HashMap<&str, Vec<&OutboxEntry>> = HashMap::new();
+        for entry in &entries {
+            by_notifier.entry(entry.notifier.as_str()).or_default().push(entry);
+        }
+
+        for (name, entries) in by_notifier {
+            // Entries for a notifier that is not configured here are left to a worker that has it
+            let Some(notifier) = self.routes.get(name) else {
+                warn!("{} queued notifications for {} are waiting, but it is not configured", entries.len(), name);
+                for entry in entries {
+                    self.release_unroutable(entry, now).await?;
+                }
+                continue;
+            };
+
+            let events: Vec<_> = entries.iter().map(|entry| entry.event.clone()).collect();
+            let results = notifier.notify_batch(&events).await;
+            if results.len() != entries.len() {
+                warn!("{} returned {} results for {} notifications", name, results.len(), entries.len());
+            }
+
+            // An entry the notifier returned no result for counts as failed, so it is retried
+            let mut results = results.into_iter();
+            let mut delivered = 0;
+            for entry in entries {
+                let result = results
+                    .next()
+                    .unwrap_or_else(|| Err(anyhow!("Notifier returned no result for this notification")));
+                match result {
+                    Ok(()) => {
+                        self.database.mark_delivered(entry, Utc::now()).await?;
+                        delivered += 1;
+                    }
+                    Err(e) => self.record_failure(entry, &format!("{e:#}")).await?,
+                }
+            }
+
+            if delivered > 0 {
+                info!("Delivered {} of {} queued {} notifications", delivered, events.len(), name);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Schedule a retry for a failed entry, or dead-letter it once it has no attempts left
+    ///
+    /// # Arguments
+    /// * `entry` - The entry that failed
+    /// * `message` - Description of the failure
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success or database error
+    async fn record_failure(&self, entry: &OutboxEntry, message: &str) -> Result<()> {
+        let attempts = entry.attempts + 1;
+
+        let Some(next_attempt_at) = next_attempt_at(attempts, Utc::now()) else {
+            error!(
+                "Giving up on {} notification {} after {} attempts: {}",
+                entry.notifier, entry.id, attempts, message
+            );
+            return self.database.mark_failed(entry.id, None, message).await;
+        };
+
+        warn!(
+            "Failed to send {} notification {} (attempt {}/{}), retrying at {}: {}",
+            entry.notifier, entry.id, attempts, MAX_ATTEMPTS, next_attempt_at, message
+        );
+        self.database.mark_failed(entry.id, Some(next_attempt_at), message).await
+    }
+
+    /// Release the claim on an entry this worker has no notifier for, so a worker of another
+    /// instance that has it can deliver it right away
+    ///
+    /// Each release counts as a failed attempt, so an entry no worker can route ends up as a
+    /// dead letter instead of being claimed forever.
+    ///
+    /// # Arguments
+    /// * `entry` - The unroutable entry
+    /// * `now` - When the entry was claimed, and so when it is due again
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success or database error
+    async fn release_unroutable(&self, entry: &OutboxEntry, now: DateTime<Utc>) -> Result<()> {
+        let attempts = entry.attempts + 1;
+        let message = format!("Notifier {} is not configured", entry.notifier);
+
+        if attempts >= MAX_ATTEMPTS {
+            error!(
+                "Giving up on {} notification {} after {} attempts: {}",
+                entry.notifier, entry.id, attempts, message
+            );
+            return self.database.mark_failed(entry.id, None, &message).await;
+        }
+
+        self.database.mark_failed(entry.id, Some(now), &message).await
+    }
+}
+
+/// Compute when to retry an entry, doubling the delay after every failed attempt
+///
+/// # Arguments
+/// * `attempts` - Number of failed attempts so far, at least one
+/// * `now` - Current time
+///
+/// # Returns
+/// * `Option<DateTime<Utc>>` - Time of the next attempt, or `None` once the entry has used up
+///   its `MAX_ATTEMPTS` and becomes a dead letter
+fn next_attempt_at(attempts: u32, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if attempts >= MAX_ATTEMPTS {
+        return None;
+    }
+
+    let delay = 2_i32
+        .checked_pow(attempts.saturating_sub(1))
+        .and_then(|factor| BASE_RETRY_DELAY.checked_mul(factor))
+        .map_or(MAX_RETRY_DELAY, |delay| delay.min(MAX_RETRY_DELAY));
+
+    Some(now + delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_after_every_failure() {
+        let now = Utc::now();
+        assert_eq!(next_attempt_at(1, now), Some(now + chrono::Duration::minutes(1)));
+        assert_eq!(next_attempt_at(2, now), Some(now + chrono::Duration::minutes(2)));
+        assert_eq!(next_attempt_at(3, now), Some(now + chrono::Duration::minutes(4)));
+        assert_eq!(next_attempt_at(6, now), Some(now + chrono::Duration::minutes(32)));
+    }
+
+    #[test]
+    fn retry_delay_is_capped() {
+        let now = Utc::now();
+        assert_eq!(next_attempt_at(7, now), Some(now + MAX_RETRY_DELAY));
+    }
+
+    #[test]
+    fn entry_becomes_dead_letter_after_max_attempts() {
+        let now = Utc::now();
+        assert!(next_attempt_at(MAX_ATTEMPTS - 1, now).is_some());
+        assert_eq!(next_attempt_at(MAX_ATTEMPTS, now), None);
+        assert_eq!(next_attempt_at(u32::MAX, now), None);
+    }
+}
@@ -0,0 +1,474 @@
+//! Boolean keyword queries matched against listing titles
+//!
+//! A query combines terms with `AND`, `OR` and `NOT` (upper case), groups them with
+//! parentheses and quotes phrases, e.g. `N-1 AND (navy OR khaki) NOT repro`. Terms next to
+//! each other without an operator must both match. `NOT` binds tightest, then `AND`, then `OR`.
+//!
+//! Matching is case-insensitive and works on whole words: `deck` matches "Deck Jacket" but
+//! not "Decks". Words are runs of letters and digits, so `N-1` is the phrase "n 1" and
+//! matches "N-1" and "N 1". Within a word `*` matches any number of characters and `?`
+//! matches exactly one, so `jack*` matches "Jacket" and "Jackets".
+
+use anyhow::{Result, bail};
+use std::fmt;
+
+/// A parsed query, ready to be matched against titles
+#[derive(Debug, Clone)]
+pub struct Query {
+    root: Node,
+    source: String,
+}
+
+/// Node of a parsed query
+#[derive(Debug, Clone)]
+enum Node {
+    /// Words that must appear consecutively
+    Phrase(Vec<String>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+}
+
+/// Token produced by the query lexer
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    /// A bare word or a quoted phrase
+    Term(String),
+}
+
+impl Token {
+    /// Describe the token for error messages
+    fn describe(&self) -> String {
+        match self {
+            Self::Open => "'('".to_string(),
+            Self::Close => "')'".to_string(),
+            Self::And => "AND".to_string(),
+            Self::Or => "OR".to_string(),
+            Self::Not => "NOT".to_string(),
+            Self::Term(term) => format!("{term:?}"),
+        }
+    }
+}
+
+impl Query {
+    /// Parse a query
+    ///
+    /// # Arguments
+    /// * `source` - Query text, e.g. `N-1 AND (navy OR khaki) NOT repro`
+    ///
+    /// # Returns
+    /// * `Result<Self>` - The parsed query, or an error describing the problem and its column
+    pub fn parse(source: &str) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            bail!("query is empty");
+        }
+
+        let mut parser = Parser { tokens, position: 0 };
+        let root = parser.parse_or()?;
+        if let Some((token, column)) = parser.tokens.get(parser.position) {
+            bail!("unexpected {} at column {}", token.describe(), column);
+        }
+
+        Ok(Self {
+            root,
+            source: source.to_string(),
+        })
+    }
+
+    /// Build a query matching any of several phrases
+    ///
+    /// # Arguments
+    /// * `phrases` - Phrases such as search terms, each matched as whole consecutive words
+    ///
+    /// # Returns
+    /// * `Result<Self>` - The query, or an error if there are no phrases or one has no words
+    pub fn any_phrase(phrases: &[String]) -> Result<Self> {
+        let mut root: Option<Node> = None;
+        for phrase in phrases {
+            let words = words(phrase, true);
+            if words.is_empty() {
+                bail!("search term {phrase:?} contains no letters or digits");
+            }
+
+            let node = Node::Phrase(words);
+            root = Some(match root {
+                Some(left) => Node::Or(Box::new(left), Box::new(node)),
+                None => node,
+            });
+        }
+
+        let Some(root) = root else {
+            bail!("at least one search term is required");
+        };
+
+        let source = phrases
+            .iter()
+            .map(|phrase| format!("\"{}\"", phrase.replace('"', "")))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        Ok(Self { root, source })
+    }
+
+    /// Check whether a title matches this query
+    ///
+    /// # Arguments
+    /// * `title` - The listing title
+    ///
+    /// # Returns
+    /// * `bool` - `true` if the title matches
+    pub fn matches(&self, title: &str) -> bool {
+        self.root.matches(&words(title, false))
+    }
+}
+
+/// Check whether text contains a phrase as whole consecutive words, the way query terms match
+///
+/// # Arguments
+/// * `text` - Text to search, such as a listing title
+/// * `phrase` - Words to find, possibly with `*` and `?` wildcards
+///
+/// # Returns
+/// * `bool` - `true` if the phrase appears; a phrase without letters or digits never does
+pub fn contains_phrase(text: &str, phrase: &str) -> bool {
+    let pattern = words(phrase, true);
+    !pattern.is_empty() && Node::Phrase(pattern).matches(&words(text, false))
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Node {
+    /// Evaluate the node against the words of a title
+    fn matches(&self, title: &[String]) -> bool {
+        match self {
+            Self::Phrase(pattern) => title
+                .windows(pattern.len())
+                .any(|window| window.iter().zip(pattern).all(|(word, glob)| glob_matches(glob, word))),
+            Self::And(left, right) => left.matches(title) && right.matches(title),
+            Self::Or(left, right) => left.matches(title) || right.matches(title),
+            Self::Not(inner) => !inner.matches(title),
+        }
+    }
+}
+
+/// Recursive descent parser over query tokens and their columns
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl Parser {
+    /// Look at the next token without consuming it
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    /// Consume the next token
+    fn next(&mut self) -> Option<(Token, usize)> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// `or := and ("OR" and)*`
+    fn parse_or(&mut self) -> Result<Node> {
+        let mut left = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            let right = self.parse_and()?;
+            left = Node::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    /// `and := unary (("AND")? unary)*`
+    fn parse_and(&mut self) -> Result<Node> {
+        let mut left = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                Some(Token::Term(_) | Token::Open | Token::Not) => {}
+                _ => break,
+            }
+            let right = self.parse_unary()?;
+            left = Node::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    /// `unary := "NOT" unary | "(" or ")" | term`
+    fn parse_unary(&mut self) -> Result<Node> {
+        match self.next() {
+            Some((Token::Not, _)) => Ok(Node::Not(Box::new(self.parse_unary()?))),
+            Some((Token::Open, column)) => {
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some((Token::Close, _)) => Ok(inner),
+                    _ => bail!("missing ')' for the '(' at column {column}"),
+                }
+            }
+            Some((Token::Term(term), column)) => {
+                let words = words(&term, true);
+                if words.is_empty() {
+                    bail!("term {term:?} at column {column} contains no letters or digits");
+                }
+                Ok(Node::Phrase(words))
+            }
+            Some((token, column)) => bail!("expected a term but found {} at column {}", token.describe(), column),
+            None => bail!("query ends where a term was expected"),
+        }
+    }
+}
+
+/// Split a query into tokens paired with their 1-based column
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().enumerate().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        let column = index + 1;
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push((Token::Open, column)),
+            ')' => tokens.push((Token::Close, column)),
+            '"' => {
+                let mut phrase = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, c)) => phrase.push(c),
+                        None => bail!("unterminated quote starting at column {column}"),
+                    }
+                }
+                tokens.push((Token::Term(phrase), column));
+            }
+            c => {
+                let mut word = String::from(c);
+                while let Some(&(_, next)) = chars.peek() {
+                    if next.is_whitespace() || matches!(next, '(' | ')' | '"') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+
+                let token = match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Term(word),
+                };
+                tokens.push((token, column));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Split text into lower-case words of letters and digits, optionally keeping wildcards
+fn words(text: &str, keep_wildcards: bool) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || (keep_wildcards && matches!(c, '*' | '?'))))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Match a word against a pattern where `*` matches any run of characters and `?` one character
+fn glob_matches(pattern: &str, word: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let word: Vec<char> = word.chars().collect();
+
+    let (mut p, mut w) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while w < word.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, w));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == word[w] => {
+                p += 1;
+                w += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character and retry
+                Some((star, matched)) => {
+                    p = star + 1;
+                    w = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse a query and match it against a title
+    fn matches(query: &str, title: &str) -> bool {
+        Query::parse(query).expect("query should parse").matches(title)
+    }
+
+    /// Parse a query that is expected to fail and return its error message
+    fn parse_error(query: &str) -> String {
+        Query::parse(query).expect_err("query should not parse").to_string()
+    }
+
+    #[test]
+    fn adjacent_terms_and_and_require_both_words() {
+        for query in ["navy khaki", "navy AND khaki"] {
+            assert!(matches(query, "Navy and Khaki N-1"));
+            assert!(!matches(query, "Navy N-1"));
+            assert!(!matches(query, "Khaki N-1"));
+        }
+    }
+
+    #[test]
+    fn or_requires_either_word() {
+        assert!(matches("navy OR khaki", "Navy N-1"));
+        assert!(matches("navy OR khaki", "Khaki N-1"));
+        assert!(!matches("navy OR khaki", "Olive N-1"));
+    }
+
+    #[test]
+    fn not_excludes_a_word() {
+        assert!(matches("NOT repro", "N-1 Deck Jacket"));
+        assert!(!matches("NOT repro", "N-1 Deck Jacket Repro"));
+        assert!(matches("deck NOT repro", "Deck Jacket"));
+        assert!(!matches("deck NOT repro", "Repro Deck Jacket"));
+    }
+
+    #[test]
+    fn parentheses_group_terms() {
+        let query = "N-1 AND (navy OR khaki)";
+        assert!(matches(query, "N-1 Navy"));
+        assert!(matches(query, "N-1 Khaki"));
+        assert!(!matches(query, "Khaki Shirt"));
+        assert!(!matches(query, "N-1 Olive"));
+    }
+
+    #[test]
+    fn quoted_phrases_need_consecutive_words_in_order() {
+        assert!(matches("\"deck jacket\"", "N-1 Deck Jacket Navy"));
+        assert!(!matches("\"deck jacket\"", "Deck Style Jacket"));
+        assert!(!matches("\"deck jacket\"", "Jacket Deck"));
+    }
+
+    #[test]
+    fn wildcards_match_within_a_word() {
+        assert!(matches("jack*", "Jacket"));
+        assert!(matches("jack*", "Jackets"));
+        assert!(matches("jack*", "Jack"));
+        assert!(!matches("jack*", "Hijacked"));
+        assert!(matches("d?ck", "Deck"));
+        assert!(matches("d?ck", "Dock"));
+        assert!(!matches("d?ck", "Dck"));
+        assert!(!matches("d?ck", "Decks"));
+        assert!(matches("*ck*", "Jacket"));
+    }
+
+    #[test]
+    fn not_binds_tighter_than_and() {
+        // (NOT navy) AND khaki
+        assert!(matches("NOT navy khaki", "Khaki N-1"));
+        assert!(!matches("NOT navy khaki", "Navy Khaki N-1"));
+        assert!(!matches("NOT navy khaki", "Olive N-1"));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        // (N-1 AND navy) OR khaki
+        let query = "N-1 navy OR khaki";
+        assert!(matches(query, "N-1 Navy"));
+        assert!(matches(query, "Khaki Shirt"));
+        assert!(!matches(query, "Navy Shirt"));
+
+        // navy OR (khaki AND N-1)
+        let query = "navy OR khaki AND N-1";
+        assert!(matches(query, "Navy Shirt"));
+        assert!(matches(query, "Khaki N-1"));
+        assert!(!matches(query, "Khaki Shirt"));
+    }
+
+    #[test]
+    fn lower_case_operators_are_terms() {
+        assert!(!matches("navy or khaki", "Navy N-1"));
+        assert!(matches("navy or khaki", "Navy or Khaki"));
+    }
+
+    #[test]
+    fn matching_ignores_case() {
+        assert!(matches("DECK jacket", "n-1 deck JACKET"));
+    }
+
+    #[test]
+    fn words_must_match_whole() {
+        assert!(matches("deck", "Deck Jacket"));
+        assert!(!matches("deck", "Decks"));
+        assert!(matches("N-1", "N-1 Deck Jacket"));
+        assert!(matches("N-1", "N 1 Deck Jacket"));
+        assert!(!matches("N-1", "N-10 Deck Jacket"));
+    }
+
+    #[test]
+    fn any_phrase_matches_each_phrase_as_whole_words() {
+        let query = Query::any_phrase(&["deck jacket".to_string(), "n-1".to_string()]).expect("phrases are valid");
+        assert!(query.matches("Deck Jacket"));
+        assert!(query.matches("N-1 Navy"));
+        assert!(!query.matches("N-10 Navy"));
+        assert!(!query.matches("Jacket Deck"));
+        assert_eq!(query.to_string(), "\"deck jacket\" OR \"n-1\"");
+    }
+
+    #[test]
+    fn any_phrase_rejects_empty_input() {
+        assert!(Query::any_phrase(&[]).is_err());
+        assert!(Query::any_phrase(&["--".to_string()]).is_err());
+    }
+
+    #[test]
+    fn display_shows_the_source() {
+        let query = Query::parse("N-1 AND (navy OR khaki)").expect("query should parse");
+        assert_eq!(query.to_string(), "N-1 AND (navy OR khaki)");
+    }
+
+    #[test]
+    fn contains_phrase_matches_whole_words() {
+        assert!(contains_phrase("N-1 Deck Jacket", "n-1"));
+        assert!(!contains_phrase("N-10 Deck Jacket", "n-1"));
+        assert!(contains_phrase("Repro N-1", "repro"));
+        assert!(!contains_phrase("Reproduction-grade N-1", "repro"));
+        assert!(contains_phrase("N-1 Deck Jacket", "deck jacket"));
+        assert!(contains_phrase("Reproduction N-1", "repro*"));
+        assert!(!contains_phrase("N-1 Deck Jacket", "--"));
+    }
+
+    #[test]
+    fn parse_errors_name_the_problem_and_column() {
+        assert_eq!(parse_error("N-1 AND (navy OR khaki"), "missing ')' for the '(' at column 9");
+        assert_eq!(parse_error("navy)"), "unexpected ')' at column 5");
+        assert_eq!(parse_error("OR navy"), "expected a term but found OR at column 1");
+        assert_eq!(parse_error("navy AND"), "query ends where a term was expected");
+        assert_eq!(parse_error("deck \"navy"), "unterminated quote starting at column 6");
+        assert_eq!(parse_error("navy \"--\""), "term \"--\" at column 6 contains no letters or digits");
+        assert_eq!(parse_error("   "), "query is empty");
+    }
+}
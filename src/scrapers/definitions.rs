@@ -0,0 +1,88 @@
+//! Discovery of declarative scraper definitions stored as TOML files
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::scrapers::shopify::ShopifyConfig;
+use crate::scrapers::{RateLimiter, SelectorScraper, ShopifyScraper};
+use crate::traits::{ScraperConfig, WebsiteScraper};
+
+/// Default directory holding scraper definitions
+pub const DEFAULT_DEFINITIONS_DIR: &str = "scrapers";
+
+/// Get the scraper definitions directory from the environment
+///
+/// # Returns
+/// * `PathBuf` - Value of `SCRAPERS_DIR`, or `scrapers` when unset
+pub fn definitions_dir() -> PathBuf {
+    std::env::var("SCRAPERS_DIR").map_or_else(|_| PathBuf::from(DEFAULT_DEFINITIONS_DIR), PathBuf::from)
+}
+
+/// Load every `*.toml` scraper definition in a directory
+///
+/// # Arguments
+/// * `dir` - Directory to scan; a missing directory yields no scrapers
+/// * `limiter` - Rate limiter shared by every scraper
+///
+/// # Returns
+/// * `Result<Vec<Arc<dyn WebsiteScraper>>>` - Scrapers sorted by file name, or the first invalid definition
+pub fn load_definitions(dir: &Path, limiter: &Arc<RateLimiter>) -> Result<Vec<Arc<dyn WebsiteScraper>>> {
+    if !dir.is_dir() {
+        info!("No scraper definitions directory at {}", dir.display());
+        return Ok(Vec::new());
+    }
+
+    let mut paths = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read scraper definitions directory {}", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "toml"));
+    paths.sort();
+
+    let mut scrapers = Vec::with_capacity(paths.len());
+    for path in paths {
+        let scraper = load_definition(&path, Arc::clone(limiter))
+            .with_context(|| format!("Invalid scraper definition {}", path.display()))?;
+        info!("Loaded scraper definition '{}' from {}", scraper.config().name, path.display());
+        scrapers.push(scraper);
+    }
+
+    Ok(scrapers)
+}
+
+/// Load a single scraper definition, dispatching on its optional `type` key
+///
+/// Definitions without a `type` (or with `type = "selector"`) describe a CSS selector scraper;
+/// `type = "shopify"` describes a Shopify storefront.
+///
+/// # Arguments
+/// * `path` - Path to the TOML definition
+/// * `limiter` - Rate limiter shared by every scraper
+///
+/// # Returns
+/// * `Result<Arc<dyn WebsiteScraper>>` - The configured scraper or a parse/validation error
+fn load_definition(path: &Path, limiter: Arc<RateLimiter>) -> Result<Arc<dyn WebsiteScraper>> {
+    let contents = std::fs::read_to_string(path).context("Failed to read file")?;
+    let mut table: toml::Table = toml::from_str(&contents).context("Failed to parse TOML")?;
+
+    let kind = match table.remove("type") {
+        Some(toml::Value::String(kind)) => kind,
+        Some(other) => bail!("`type` must be a string, found {}", other.type_str()),
+        None => "selector".to_string(),
+    };
+
+    match kind.as_str() {
+        "selector" => {
+            let config = ScraperConfig::deserialize(table)?;
+            Ok(Arc::new(SelectorScraper::new(config, limiter)?))
+        }
+        "shopify" => {
+            let shop = ShopifyConfig::deserialize(table)?;
+            Ok(Arc::new(ShopifyScraper::new(shop, limiter)?))
+        }
+        other => bail!("unknown scraper `type` {other:?}, expected \"selector\" or \"shopify\""),
+    }
+}
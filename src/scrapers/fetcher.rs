@@ -0,0 +1,601 @@
+//! HTTP fetching shared by every scraper, throttled per host, checked against robots.txt
+//! and retried on transient failures
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, Response, Url};
+use std::collections::HashMap;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use crate::scrapers::robots::RobotsTxt;
+use crate::traits::{RateLimit, RetryPolicy, ScraperConfig};
+
+/// User agent sent with every scraper request, naming the bot by the product token its
+/// robots.txt rules are looked up with ([`ROBOTS_AGENT`]) so sites can identify and block it
+///
+/// [`ROBOTS_AGENT`]: crate::scrapers::robots::ROBOTS_AGENT
+pub const USER_AGENT: &str = concat!("jacket-finder/", env!("CARGO_PKG_VERSION"));
+
+/// How long a fetched robots.txt is used before it is fetched again
+const ROBOTS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Parsed robots.txt files by origin, with when they were fetched
+type RobotsCache = HashMap<String, (Instant, Arc<RobotsTxt>)>;
+
+/// Token bucket limiting the requests sent to one host
+#[derive(Debug)]
+struct TokenBucket {
+    /// Tokens added per second
+    per_second: f64,
+    /// Maximum number of tokens, i.e. the burst
+    capacity: f64,
+    /// Requests that may be sent right away
+    tokens: f64,
+    /// When `tokens` was last refilled
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// Create a full bucket
+    fn new(per_second: f64, capacity: f64, now: Instant) -> Self {
+        Self {
+            per_second,
+            capacity,
+            tokens: capacity,
+            updated: now,
+        }
+    }
+
+    /// Adopt the stricter of the current rate and another one for the same host
+    fn tighten(&mut self, per_second: f64, capacity: f64) {
+        self.per_second = self.per_second.min(per_second);
+        self.capacity = self.capacity.min(capacity);
+        self.tokens = self.tokens.min(self.capacity);
+    }
+
+    /// Take a token if one is available
+    ///
+    /// # Arguments
+    /// * `now` - Current time, used to refill the bucket
+    ///
+    /// # Returns
+    /// * `Option<Duration>` - `None` if a token was taken, otherwise how long until one is available
+    fn try_take(&mut self, now: Instant) -> Option<Duration> {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.capacity);
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64((1.0 - self.tokens) / self.per_second))
+        }
+    }
+}
+
+/// Request budgets per host, shared by every scraper so that scrapers for the same
+/// shop draw from one budget
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// Wait until a request to a host is allowed
+    ///
+    /// When several scrapers with different limits share a host, the strictest limit applies.
+    /// A crawl delay spaces every request to the host at least that far apart.
+    ///
+    /// # Arguments
+    /// * `host` - Host and port the request goes to
+    /// * `limit` - The requesting scraper's limit
+    /// * `crawl_delay` - Minimum time between requests asked for by the host's robots.txt
+    pub async fn acquire(&self, host: &str, limit: RateLimit, crawl_delay: Option<Duration>) {
+        while let Some(wait) = self.try_acquire(host, limit, crawl_delay, Instant::now()) {
+            sleep(wait).await;
+        }
+    }
+
+    /// Take a token for a request to a host if one is available
+    ///
+    /// # Arguments
+    /// * `host` - Host and port the request goes to
+    /// * `limit` - The requesting scraper's limit
+    /// * `crawl_delay` - Minimum time between requests asked for by the host's robots.txt
+    /// * `now` - Current time
+    ///
+    /// # Returns
+    /// * `Option<Duration>` - `None` if the request may be sent, otherwise how long to wait before trying again
+    fn try_acquire(&self, host: &str, limit: RateLimit, crawl_delay: Option<Duration>, now: Instant) -> Option<Duration> {
+        let (per_second, capacity) = bucket_rate(limit, crawl_delay);
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        let bucket = buckets
+            .entry(host.to_string())
+            .or_insert_with(|| TokenBucket::new(per_second, capacity, now));
+        bucket.tighten(per_second, capacity);
+        bucket.try_take(now)
+    }
+}
+
+/// Refill rate and capacity of the token bucket for a limit
+///
+/// # Arguments
+/// * `limit` - The scraper's limit
+/// * `crawl_delay` - Minimum time between requests asked for by the host's robots.txt
+///
+/// # Returns
+/// * `(f64, f64)` - Tokens per second and burst; a crawl delay caps the rate and allows no burst
+fn bucket_rate(limit: RateLimit, crawl_delay: Option<Duration>) -> (f64, f64) {
+    let per_second = f64::from(limit.requests_per_minute) / 60.0;
+    let capacity = f64::from(limit.burst);
+    match crawl_delay.filter(|delay| !delay.is_zero()) {
+        Some(delay) => (per_second.min(1.0 / delay.as_secs_f64()), 1.0),
+        None => (per_second, capacity),
+    }
+}
+
+/// HTTP client for one scraper, sending every request through the shared rate limiter,
+/// refusing URLs the site's robots.txt disallows and retrying transient failures
+#[derive(Clone)]
+pub struct Fetcher {
+    client: Client,
+    rate_limit: RateLimit,
+    limiter: Arc<RateLimiter>,
+    retry: RetryPolicy,
+    /// Whether robots.txt is skipped because the site permits scraping
+    ignore_robots_txt: bool,
+    /// Parsed robots.txt files by origin, shared by clones of the fetcher
+    robots: Arc<Mutex<RobotsCache>>,
+}
+
+impl Fetcher {
+    /// Create a fetcher for a scraper
+    ///
+    /// # Arguments
+    /// * `config` - The scraper's configuration, for its rate limit, robots.txt override and retry policy
+    /// * `limiter` - Rate limiter shared by every scraper
+    ///
+    /// # Returns
+    /// * `Result<Self>` - New fetcher, or an error for an invalid limit, retry policy or HTTP client
+    pub fn new(config: &ScraperConfig, limiter: Arc<RateLimiter>) -> Result<Self> {
+        if config.rate_limit.requests_per_minute == 0 {
+            bail!("`rate_limit.requests_per_minute` must be at least 1");
+        }
+        if config.rate_limit.burst == 0 {
+            bail!("`rate_limit.burst` must be at least 1");
+        }
+        if config.retry.max_attempts == 0 {
+            bail!("`retry.max_attempts` must be at least 1");
+        }
+        if !(0.0..=1.0).contains(&config.retry.jitter) {
+            bail!("`retry.jitter` must be between 0 and 1");
+        }
+
+        let client = Client::builder().user_agent(USER_AGENT).build()?;
+
+        Ok(Self {
+            client,
+            rate_limit: config.rate_limit,
+            limiter,
+            retry: config.retry.clone(),
+            ignore_robots_txt: config.ignore_robots_txt,
+            robots: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Check whether the site's robots.txt allows fetching a URL
+    ///
+    /// # Arguments
+    /// * `url` - Absolute URL about to be fetched
+    ///
+    /// # Returns
+    /// * `Result<bool>` - Whether the URL may be fetched, or an error if robots.txt could not be fetched
+    pub async fn allows(&self, url: &str) -> Result<bool> {
+        let parsed = parse_url(url)?;
+        let robots = self.robots_for(&parsed).await?;
+        Ok(robots.is_none_or(|robots| robots.is_allowed(&request_path(&parsed))))
+    }
+
+    /// Send a GET request once the host's rate limit allows it, retrying transient failures
+    ///
+    /// # Arguments
+    /// * `url` - Absolute URL to fetch
+    ///
+    /// # Returns
+    /// * `Result<Response>` - The first response not worth retrying, or the last one once the
+    ///   attempts are used up; an error for an invalid, disallowed or unreachable URL
+    pub async fn get(&self, url: &str) -> Result<Response> {
+        let parsed = parse_url(url)?;
+        let robots = self.robots_for(&parsed).await?;
+        if let Some(robots) = &robots
+            && !robots.is_allowed(&request_path(&parsed))
+        {
+            bail!("robots.txt of {} disallows {}", parsed.origin().ascii_serialization(), url);
+        }
+
+        self.send(&parsed, robots.and_then(|robots| robots.crawl_delay)).await
+    }
+
+    /// Send a GET request through the rate limiter, without checking robots.txt
+    ///
+    /// Network errors and responses with a retryable status are retried with exponential
+    /// backoff and jitter, waiting for `Retry-After` instead when the response has one.
+    ///
+    /// # Arguments
+    /// * `url` - URL to fetch
+    /// * `crawl_delay` - Minimum time between requests asked for by the host's robots.txt
+    ///
+    /// # Returns
+    /// * `Result<Response>` - The first response not worth retrying, or the last one once the
+    ///   attempts are used up; an error if the last attempt could not reach the host
+    async fn send(&self, url: &Url, crawl_delay: Option<Duration>) -> Result<Response> {
+        let host = host_key(url);
+        let max_attempts = self.retry.max_attempts;
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+            self.limiter.acquire(&host, self.rate_limit, crawl_delay).await;
+
+            let response = match self.client.get(url.clone()).send().await {
+                Ok(response) => response,
+                Err(e) if attempt < max_attempts => {
+                    let wait = self.retry.backoff(attempt, random_fraction());
+                    warn!("Request to {url} failed (attempt {attempt}/{max_attempts}), retrying in {wait:?}: {e}");
+                    sleep(wait).await;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let status = response.status();
+            if attempt >= max_attempts || !self.retry.retry_statuses.contains(&status.as_u16()) {
+                return Ok(response);
+            }
+
+            let requested = retry_after(response.headers(), Utc::now());
+            let Some(wait) = self.retry.delay(attempt, requested, random_fraction()) else {
+                warn!(
+                    "{url} returned {status} and asks to retry in {:?}, longer than the retry policy allows",
+                    requested.unwrap_or_default()
+                );
+                return Ok(response);
+            };
+            warn!("{url} returned {status} (attempt {attempt}/{max_attempts}), retrying in {wait:?}");
+            sleep(wait).await;
+        }
+    }
+
+    /// Get the robots.txt rules for a URL's site, fetching them unless cached
+    ///
+    /// A missing robots.txt (any 4xx response) allows everything. A server error or network
+    /// failure that persists through the retry policy is an error, since the rules are unknown.
+    ///
+    /// # Arguments
+    /// * `url` - URL on the site
+    ///
+    /// # Returns
+    /// * `Result<Option<Arc<RobotsTxt>>>` - The rules, `None` if this scraper ignores robots.txt,
+    ///   or an error if they could not be fetched
+    async fn robots_for(&self, url: &Url) -> Result<Option<Arc<RobotsTxt>>> {
+        if self.ignore_robots_txt {
+            return Ok(None);
+        }
+
+        let origin = url.origin().ascii_serialization();
+        let cached = self
+            .robots_lock()
+            .get(&origin)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < ROBOTS_TTL)
+            .map(|(_, robots)| Arc::clone(robots));
+        if cached.is_some() {
+            return Ok(cached);
+        }
+
+        let robots_url = url.join("/robots.txt")?;
+        info!("Fetching {}", robots_url);
+        let response = self
+            .send(&robots_url, None)
+            .await
+            .with_context(|| format!("Failed to fetch {robots_url}"))?;
+
+        let status = response.status();
+        let robots = if status.is_success() {
+            RobotsTxt::parse(&response.text().await?)
+        } else if status.is_client_error() {
+            RobotsTxt::default()
+        } else {
+            bail!("Failed to fetch {}: {}", robots_url, status);
+        };
+
+        let robots = Arc::new(robots);
+        self.robots_lock().insert(origin, (Instant::now(), Arc::clone(&robots)));
+        Ok(Some(robots))
+    }
+
+    /// Lock the robots.txt cache, recovering from a poisoned lock
+    fn robots_lock(&self) -> MutexGuard<'_, RobotsCache> {
+        self.robots.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl RetryPolicy {
+    /// Longest delay between attempts
+    fn max_backoff(&self) -> Duration {
+        Duration::from_millis(self.max_backoff_ms)
+    }
+
+    /// Delay before retrying after a failed attempt
+    ///
+    /// # Arguments
+    /// * `attempt` - Number of the attempt that failed, starting at 1
+    /// * `random` - Random fraction between 0 and 1 picking how much jitter to apply
+    ///
+    /// # Returns
+    /// * `Duration` - The exponential backoff for the attempt, capped and with its jitter applied
+    fn backoff(&self, attempt: u32, random: f64) -> Duration {
+        let backoff = Duration::from_millis(self.initial_backoff_ms)
+            .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff());
+        backoff.mul_f64(1.0 - self.jitter * random)
+    }
+
+    /// Delay before retrying a response with a retryable status
+    ///
+    /// # Arguments
+    /// * `attempt` - Number of the attempt that failed, starting at 1
+    /// * `retry_after` - Wait the response asked for, if any
+    /// * `random` - Random fraction between 0 and 1 picking how much jitter to apply
+    ///
+    /// # Returns
+    /// * `Option<Duration>` - The requested wait, or the backoff without one; `None` if the
+    ///   response asks for longer than `max_backoff_ms` and should not be retried
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>, random: f64) -> Option<Duration> {
+        match retry_after {
+            Some(wait) if wait > self.max_backoff() => None,
+            Some(wait) => Some(wait),
+            None => Some(self.backoff(attempt, random)),
+        }
+    }
+}
+
+/// Read how long a response asks to wait before retrying
+///
+/// # Arguments
+/// * `headers` - Headers of the response
+/// * `now` - Current time, to turn an HTTP date into a wait
+///
+/// # Returns
+/// * `Option<Duration>` - Wait from `Retry-After` in seconds or as an HTTP date (zero once it
+///   has passed), or `None` without a valid header
+fn retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+/// Pseudo-random number between 0 and 1, good enough to spread out retries
+fn random_fraction() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    f64::from(u32::try_from(bits >> 32).unwrap_or_default()) / f64::from(u32::MAX)
+}
+
+/// Parse an absolute URL, naming it on failure
+fn parse_url(url: &str) -> Result<Url> {
+    Url::parse(url).with_context(|| format!("Invalid URL {url:?}"))
+}
+
+/// Host and port of a URL, identifying its rate limit bucket
+fn host_key(url: &Url) -> String {
+    format!(
+        "{}:{}",
+        url.host_str().unwrap_or_default(),
+        url.port_or_known_default().unwrap_or_default()
+    )
+}
+
+/// Path and query of a URL, as matched against robots.txt rules
+fn request_path(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOST: &str = "shop.example:443";
+
+    /// Limit of `requests_per_minute` with a burst of `burst`
+    fn limit(requests_per_minute: u32, burst: u32) -> RateLimit {
+        RateLimit {
+            requests_per_minute,
+            burst,
+        }
+    }
+
+    #[test]
+    fn burst_is_sent_at_once_then_spaced_to_the_rate() {
+        let limiter = RateLimiter::default();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert_eq!(limiter.try_acquire(HOST, limit(30, 3), None, start), None);
+        }
+        assert_eq!(limiter.try_acquire(HOST, limit(30, 3), None, start), Some(Duration::from_secs(2)));
+
+        let later = start + Duration::from_secs(1);
+        assert_eq!(limiter.try_acquire(HOST, limit(30, 3), None, later), Some(Duration::from_secs(1)));
+
+        let later = start + Duration::from_secs(2);
+        assert_eq!(limiter.try_acquire(HOST, limit(30, 3), None, later), None);
+        assert_eq!(limiter.try_acquire(HOST, limit(30, 3), None, later), Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn bucket_refills_up_to_its_burst() {
+        let limiter = RateLimiter::default();
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(limiter.try_acquire(HOST, limit(60, 3), None, start), None);
+        }
+
+        let later = start + Duration::from_secs(10 * 60);
+        for _ in 0..3 {
+            assert_eq!(limiter.try_acquire(HOST, limit(60, 3), None, later), None);
+        }
+        assert!(limiter.try_acquire(HOST, limit(60, 3), None, later).is_some());
+    }
+
+    #[test]
+    fn hosts_have_separate_buckets() {
+        let limiter = RateLimiter::default();
+        let now = Instant::now();
+        assert_eq!(limiter.try_acquire(HOST, limit(30, 1), None, now), None);
+        assert!(limiter.try_acquire(HOST, limit(30, 1), None, now).is_some());
+        assert_eq!(limiter.try_acquire("other.example:443", limit(30, 1), None, now), None);
+    }
+
+    #[test]
+    fn shared_host_keeps_the_strictest_limit() {
+        let limiter = RateLimiter::default();
+        let now = Instant::now();
+
+        assert_eq!(limiter.try_acquire(HOST, limit(6, 1), None, now), None);
+        // A more generous scraper for the same host can't loosen the bucket
+        assert_eq!(limiter.try_acquire(HOST, limit(600, 10), None, now), Some(Duration::from_secs(10)));
+
+        let mut bucket = TokenBucket::new(1.0, 5.0, now);
+        bucket.tighten(0.5, 10.0);
+        assert!((bucket.per_second - 0.5).abs() < f64::EPSILON);
+        assert!((bucket.capacity - 5.0).abs() < f64::EPSILON);
+        bucket.tighten(2.0, 2.0);
+        assert!((bucket.per_second - 0.5).abs() < f64::EPSILON);
+        assert!((bucket.tokens - 2.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn crawl_delay_caps_the_rate_and_burst() {
+        let (per_second, capacity) = bucket_rate(limit(60, 5), Some(Duration::from_secs(4)));
+        assert!((per_second - 0.25).abs() < f64::EPSILON);
+        assert!((capacity - 1.0).abs() < f64::EPSILON);
+
+        // A crawl delay shorter than the scraper's own spacing changes only the burst
+        let (per_second, capacity) = bucket_rate(limit(6, 5), Some(Duration::from_secs(1)));
+        assert!((per_second - 0.1).abs() < f64::EPSILON);
+        assert!((capacity - 1.0).abs() < f64::EPSILON);
+
+        let (per_second, capacity) = bucket_rate(limit(60, 5), Some(Duration::ZERO));
+        assert!((per_second - 1.0).abs() < f64::EPSILON);
+        assert!((capacity - 5.0).abs() < f64::EPSILON);
+
+        let limiter = RateLimiter::default();
+        let now = Instant::now();
+        let delay = Some(Duration::from_secs(4));
+        assert_eq!(limiter.try_acquire(HOST, limit(60, 5), delay, now), None);
+        assert_eq!(limiter.try_acquire(HOST, limit(60, 5), delay, now), Some(Duration::from_secs(4)));
+    }
+
+    /// Retry policy without jitter, doubling from one second up to ten
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 10_000,
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Headers with a single `Retry-After` value
+    fn retry_after_header(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, value.parse().expect("valid header value"));
+        headers
+    }
+
+    #[test]
+    fn backoff_doubles_after_every_attempt() {
+        let policy = policy();
+        assert_eq!(policy.backoff(1, 0.5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2, 0.5), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3, 0.5), Duration::from_secs(4));
+        assert_eq!(policy.backoff(4, 0.5), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn backoff_is_capped_at_the_max_delay() {
+        let policy = policy();
+        assert_eq!(policy.backoff(5, 0.0), Duration::from_secs(10));
+        assert_eq!(policy.backoff(40, 0.0), Duration::from_secs(10));
+        assert_eq!(policy.backoff(u32::MAX, 0.0), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn jitter_shortens_the_backoff_by_up_to_its_share() {
+        let policy = RetryPolicy {
+            jitter: 0.5,
+            ..policy()
+        };
+        assert_eq!(policy.backoff(2, 0.0), Duration::from_secs(2));
+        assert_eq!(policy.backoff(2, 0.5), Duration::from_millis(1500));
+        assert_eq!(policy.backoff(2, 1.0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(5, 1.0), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn delay_prefers_retry_after_within_the_max_delay() {
+        let policy = policy();
+        assert_eq!(policy.delay(1, Some(Duration::from_secs(7)), 0.0), Some(Duration::from_secs(7)));
+        assert_eq!(policy.delay(1, Some(Duration::from_secs(10)), 0.0), Some(Duration::from_secs(10)));
+        assert_eq!(policy.delay(1, Some(Duration::from_secs(11)), 0.0), None);
+        assert_eq!(policy.delay(3, None, 0.0), Some(Duration::from_secs(4)));
+    }
+
+    #[test]
+    fn retry_after_reads_seconds() {
+        let now = Utc::now();
+        assert_eq!(retry_after(&retry_after_header("120"), now), Some(Duration::from_secs(2 * 60)));
+        assert_eq!(retry_after(&retry_after_header(" 0 "), now), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_reads_http_dates() {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2026 07:28:00 GMT")
+            .expect("valid date")
+            .with_timezone(&Utc);
+        let headers = retry_after_header("Wed, 21 Oct 2026 07:28:30 GMT");
+        assert_eq!(retry_after(&headers, now), Some(Duration::from_secs(30)));
+
+        // A date in the past means the request may be retried right away
+        let headers = retry_after_header("Wed, 21 Oct 2026 07:27:00 GMT");
+        assert_eq!(retry_after(&headers, now), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_ignores_missing_and_invalid_values() {
+        let now = Utc::now();
+        assert_eq!(retry_after(&HeaderMap::new(), now), None);
+        assert_eq!(retry_after(&retry_after_header("soon"), now), None);
+        assert_eq!(retry_after(&retry_after_header("-5"), now), None);
+    }
+
+    #[test]
+    fn random_fraction_stays_between_zero_and_one() {
+        for _ in 0..100 {
+            assert!((0.0..=1.0).contains(&random_fraction()));
+        }
+    }
+}
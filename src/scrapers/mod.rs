@@ -1,5 +1,14 @@
 //! Website-specific scraper implementations
 
+pub mod definitions;
+pub mod fetcher;
 pub mod marrkt;
+pub mod robots;
+pub mod selector;
+pub mod shopify;
 
+pub use definitions::{definitions_dir, load_definitions};
+pub use fetcher::{Fetcher, RateLimiter};
 pub use marrkt::MarrktScraper;
+pub use selector::SelectorScraper;
+pub use shopify::ShopifyScraper;
@@ -0,0 +1,373 @@
+//! Parsing of robots.txt files and matching of URLs against their rules
+
+use std::time::Duration;
+
+/// Product token sent in the user agent and matched against `User-agent` lines; groups for `*` apply when none names it
+pub const ROBOTS_AGENT: &str = "jacket-finder";
+
+/// One `Allow` or `Disallow` rule
+#[derive(Debug, Clone)]
+struct Rule {
+    /// Path pattern, possibly with `*` wildcards and a trailing `$` anchor
+    pattern: String,
+    allow: bool,
+}
+
+/// The rules of a robots.txt file that apply to this bot
+#[derive(Debug, Clone, Default)]
+pub struct RobotsTxt {
+    rules: Vec<Rule>,
+    /// Minimum time between requests asked for by `Crawl-delay`
+    pub crawl_delay: Option<Duration>,
+}
+
+/// A group of rules and the user agents it applies to
+#[derive(Debug, Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<Duration>,
+}
+
+impl RobotsTxt {
+    /// Parse a robots.txt file, keeping the groups for this bot
+    ///
+    /// Groups naming [`ROBOTS_AGENT`] are used when there are any, otherwise the groups for `*`.
+    /// Unknown lines are ignored, as the format requires.
+    ///
+    /// # Arguments
+    /// * `text` - Contents of the robots.txt file
+    ///
+    /// # Returns
+    /// * `Self` - Rules for this bot; empty if no group applies, allowing everything
+    pub fn parse(text: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut reading_agents = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if !reading_agents {
+                        groups.push(Group::default());
+                        reading_agents = true;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                directive @ ("allow" | "disallow") => {
+                    reading_agents = false;
+                    // An empty `Disallow` allows everything, which is the default anyway
+                    if let (Some(group), false) = (groups.last_mut(), value.is_empty()) {
+                        group.rules.push(Rule {
+                            pattern: value.to_string(),
+                            allow: directive == "allow",
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    reading_agents = false;
+                    let delay = value.parse::<f64>().ok().filter(|delay| delay.is_finite() && *delay >= 0.0);
+                    if let (Some(group), Some(delay)) = (groups.last_mut(), delay) {
+                        group.crawl_delay = Some(Duration::from_secs_f64(delay));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let names = |group: &Group, agent: &str| group.agents.iter().any(|name| name == agent);
+        let agent = if groups.iter().any(|group| names(group, ROBOTS_AGENT)) {
+            ROBOTS_AGENT
+        } else {
+            "*"
+        };
+
+        let mut robots = Self::default();
+        for group in groups.into_iter().filter(|group| names(group, agent)) {
+            robots.rules.extend(group.rules);
+            robots.crawl_delay = robots.crawl_delay.max(group.crawl_delay);
+        }
+        robots
+    }
+
+    /// Check whether a path may be fetched
+    ///
+    /// The most specific (longest) matching rule decides, with `Allow` winning ties.
+    ///
+    /// # Arguments
+    /// * `path` - Path and query of the URL, e.g. `/search?q=jacket`
+    ///
+    /// # Returns
+    /// * `bool` - True unless a `Disallow` rule applies
+    pub fn is_allowed(&self, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// Check whether a robots.txt path pattern matches the start of a path
+///
+/// # Arguments
+/// * `pattern` - Pattern where `*` matches any characters and a trailing `$` anchors the end
+/// * `path` - Path and query to match
+///
+/// # Returns
+/// * `bool` - True if the pattern matches
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let parts: Vec<&str> = pattern.split('*').collect();
+
+    let Some(mut rest) = path.strip_prefix(parts[0]) else {
+        return false;
+    };
+    if parts.len() == 1 {
+        return !anchored || rest.is_empty();
+    }
+
+    let last = parts.len() - 1;
+    for (index, part) in parts.iter().enumerate().skip(1) {
+        if index == last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rules with a single group for every agent
+    fn rules(lines: &str) -> RobotsTxt {
+        RobotsTxt::parse(&format!("User-agent: *\n{lines}"))
+    }
+
+    /// Assert which paths a single pattern matches, as in Google's robots.txt specification
+    fn assert_pattern(pattern: &str, matching: &[&str], not_matching: &[&str]) {
+        for path in matching {
+            assert!(pattern_matches(pattern, path), "{pattern} should match {path}");
+        }
+        for path in not_matching {
+            assert!(!pattern_matches(pattern, path), "{pattern} should not match {path}");
+        }
+    }
+
+    #[test]
+    fn root_pattern_matches_everything() {
+        assert_pattern("/", &["/", "/fish", "/fish/salmon.html"], &[]);
+        assert_pattern("/*", &["/", "/fish", "/fish/salmon.html"], &[]);
+    }
+
+    #[test]
+    fn plain_patterns_match_path_prefixes_case_sensitively() {
+        let matching = [
+            "/fish",
+            "/fish.html",
+            "/fish/salmon.html",
+            "/fishheads",
+            "/fishheads/yummy.html",
+            "/fish.php?id=anything",
+        ];
+        let not_matching = ["/Fish.asp", "/catfish", "/?id=fish", "/desert/fish"];
+        assert_pattern("/fish", &matching, &not_matching);
+        assert_pattern("/fish*", &matching, &not_matching);
+    }
+
+    #[test]
+    fn trailing_slash_matches_only_the_folder() {
+        assert_pattern(
+            "/fish/",
+            &["/fish/", "/fish/?id=anything", "/fish/salmon.htm"],
+            &["/fish", "/fish.html", "/animals/fish/", "/Fish/Salmon.asp"],
+        );
+    }
+
+    #[test]
+    fn wildcard_matches_any_characters() {
+        assert_pattern(
+            "/*.php",
+            &[
+                "/index.php",
+                "/filename.php",
+                "/folder/filename.php",
+                "/folder/filename.php?parameters",
+                "/folder/any.php.file.html",
+                "/filename.php/",
+            ],
+            &["/", "/windows.PHP"],
+        );
+        assert_pattern(
+            "/fish*.php",
+            &["/fish.php", "/fishheads/catfish.php?parameters"],
+            &["/Fish.PHP"],
+        );
+    }
+
+    #[test]
+    fn dollar_anchors_the_end_of_the_path() {
+        assert_pattern(
+            "/*.php$",
+            &["/filename.php", "/folder/filename.php"],
+            &["/filename.php?parameters", "/filename.php/", "/filename.php5", "/windows.PHP"],
+        );
+        assert_pattern("/fish$", &["/fish"], &["/fish/", "/fishheads"]);
+    }
+
+    #[test]
+    fn longest_matching_rule_wins() {
+        let robots = rules("Allow: /p\nDisallow: /");
+        assert!(robots.is_allowed("/page"));
+        assert!(!robots.is_allowed("/other"));
+
+        let robots = rules("Allow: /page\nDisallow: /*.htm");
+        assert!(!robots.is_allowed("/page.htm"));
+        assert!(robots.is_allowed("/page"));
+
+        let robots = rules("Allow: /$\nDisallow: /");
+        assert!(robots.is_allowed("/"));
+        assert!(!robots.is_allowed("/page.htm"));
+    }
+
+    #[test]
+    fn allow_wins_ties() {
+        let robots = rules("Allow: /folder\nDisallow: /folder");
+        assert!(robots.is_allowed("/folder/page"));
+
+        let robots = rules("Allow: /page\nDisallow: /*.ph");
+        assert!(robots.is_allowed("/page.php5"));
+    }
+
+    #[test]
+    fn empty_disallow_and_missing_rules_allow_everything() {
+        assert!(rules("Disallow:").is_allowed("/search?q=jacket"));
+        assert!(RobotsTxt::parse("").is_allowed("/search?q=jacket"));
+        assert!(RobotsTxt::default().is_allowed("/anything"));
+    }
+
+    #[test]
+    fn robots_txt_itself_is_always_allowed() {
+        assert!(rules("Disallow: /").is_allowed("/robots.txt"));
+    }
+
+    #[test]
+    fn query_strings_are_matched() {
+        let robots = rules("Disallow: /search?q=");
+        assert!(!robots.is_allowed("/search?q=jacket"));
+        assert!(robots.is_allowed("/search"));
+    }
+
+    #[test]
+    fn groups_naming_the_bot_replace_the_wildcard_group() {
+        let text = "\
+User-agent: *
+Disallow: /
+
+User-agent: Jacket-Finder
+Disallow: /private
+";
+        let robots = RobotsTxt::parse(text);
+        assert!(robots.is_allowed("/search"));
+        assert!(!robots.is_allowed("/private/page"));
+    }
+
+    #[test]
+    fn wildcard_group_applies_when_no_group_names_the_bot() {
+        let text = "\
+User-agent: googlebot
+Disallow: /
+
+User-agent: *
+Disallow: /private
+";
+        let robots = RobotsTxt::parse(text);
+        assert!(robots.is_allowed("/search"));
+        assert!(!robots.is_allowed("/private"));
+    }
+
+    #[test]
+    fn groups_for_other_agents_are_ignored() {
+        let robots = RobotsTxt::parse("User-agent: googlebot\nDisallow: /");
+        assert!(robots.is_allowed("/search"));
+        assert_eq!(robots.crawl_delay, None);
+    }
+
+    #[test]
+    fn consecutive_user_agent_lines_share_a_group() {
+        let text = "\
+User-agent: googlebot
+User-agent: jacket-finder
+Disallow: /shared
+
+User-agent: *
+Disallow: /
+";
+        let robots = RobotsTxt::parse(text);
+        assert!(!robots.is_allowed("/shared"));
+        assert!(robots.is_allowed("/search"));
+    }
+
+    #[test]
+    fn groups_for_the_same_agent_are_merged() {
+        let text = "\
+User-agent: jacket-finder
+Disallow: /a
+Crawl-delay: 2
+
+User-agent: googlebot
+Disallow: /b
+
+User-agent: jacket-finder
+Disallow: /c
+Crawl-delay: 5
+";
+        let robots = RobotsTxt::parse(text);
+        assert!(!robots.is_allowed("/a"));
+        assert!(robots.is_allowed("/b"));
+        assert!(!robots.is_allowed("/c"));
+        assert_eq!(robots.crawl_delay, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn parses_crawl_delay_in_fractional_seconds() {
+        assert_eq!(rules("Crawl-delay: 1.5").crawl_delay, Some(Duration::from_millis(1500)));
+        assert_eq!(rules("Crawl-delay: soon").crawl_delay, None);
+        assert_eq!(rules("Crawl-delay: -1").crawl_delay, None);
+    }
+
+    #[test]
+    fn ignores_comments_unknown_lines_and_rules_outside_groups() {
+        let text = "\
+Disallow: /before-any-group
+# Disallow: /commented
+Sitemap: https://example.com/sitemap.xml
+user-agent: * # everyone
+DISALLOW: /private # keep out
+nonsense
+";
+        let robots = RobotsTxt::parse(text);
+        assert!(robots.is_allowed("/before-any-group"));
+        assert!(robots.is_allowed("/commented"));
+        assert!(!robots.is_allowed("/private"));
+    }
+}
@@ -0,0 +1,481 @@
+//! Generic CSS selector-driven scraper built entirely from a `ScraperConfig`
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use scraper::{Html, Selector};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::models::{Jacket, ListingStatus, Price, ScrapeStats, SearchResults};
+use crate::query::Query;
+use crate::scrapers::{Fetcher, RateLimiter};
+use crate::traits::{ScraperConfig, WebsiteScraper};
+
+/// Safety limit to prevent infinite pagination loops
+const MAX_PAGES: u32 = 50;
+
+/// Pre-parsed selectors for a scraper configuration
+struct CompiledSelectors {
+    product: Selector,
+    title: Selector,
+    price: Selector,
+    brand: Option<Selector>,
+    link: Selector,
+    image: Selector,
+    pagination_container: Selector,
+    pagination_next: Selector,
+    sold_out: Option<Selector>,
+    details: Option<CompiledDetailSelectors>,
+}
+
+/// Pre-parsed product detail page selectors
+struct CompiledDetailSelectors {
+    size: Option<Selector>,
+    measurements: Option<Selector>,
+    condition: Option<Selector>,
+    description: Option<Selector>,
+}
+
+impl CompiledSelectors {
+    /// Parse every selector in the configuration
+    ///
+    /// # Arguments
+    /// * `config` - Scraper configuration holding the raw selectors
+    ///
+    /// # Returns
+    /// * `Result<Self>` - Compiled selectors or an error naming the invalid field
+    fn compile(config: &ScraperConfig) -> Result<Self> {
+        let selectors = &config.selectors;
+
+        Ok(Self {
+            product: parse_selector("selectors.product_container", &selectors.product_container)?,
+            title: parse_selector("selectors.title", &selectors.title)?,
+            price: parse_selector("selectors.price", &selectors.price)?,
+            brand: selectors
+                .brand
+                .as_deref()
+                .map(|s| parse_selector("selectors.brand", s))
+                .transpose()?,
+            link: parse_selector("selectors.link", &selectors.link)?,
+            image: parse_selector("selectors.image", &selectors.image)?,
+            pagination_container: parse_selector(
+                "selectors.pagination_container",
+                &selectors.pagination_container,
+            )?,
+            pagination_next: parse_selector("selectors.pagination_next", &selectors.pagination_next)?,
+            sold_out: selectors
+                .sold_out_indicator
+                .as_deref()
+                .map(|s| parse_selector("selectors.sold_out_indicator", s))
+                .transpose()?,
+            details: config
+                .details
+                .as_ref()
+                .map(|details| -> Result<_> {
+                    Ok(CompiledDetailSelectors {
+                        size: parse_optional_selector("details.size", details.size.as_deref())?,
+                        measurements: parse_optional_selector(
+                            "details.measurements",
+                            details.measurements.as_deref(),
+                        )?,
+                        condition: parse_optional_selector("details.condition", details.condition.as_deref())?,
+                        description: parse_optional_selector(
+                            "details.description",
+                            details.description.as_deref(),
+                        )?,
+                    })
+                })
+                .transpose()?,
+        })
+    }
+}
+
+/// Parse an optional CSS selector, naming the configuration field on failure
+fn parse_optional_selector(field: &str, selector: Option<&str>) -> Result<Option<Selector>> {
+    selector.map(|s| parse_selector(field, s)).transpose()
+}
+
+/// Collect the trimmed text of every element matching a selector, one line per element
+///
+/// # Arguments
+/// * `document` - The parsed page
+/// * `selector` - Selector to match, if configured
+///
+/// # Returns
+/// * `Option<String>` - Joined text, or `None` if nothing non-empty matched
+fn select_text(document: &Html, selector: Option<&Selector>) -> Option<String> {
+    let lines: Vec<String> = document
+        .select(selector?)
+        .map(|el| el.text().collect::<Vec<_>>().join(" "))
+        .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|text| !text.is_empty())
+        .collect();
+
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Parse a single CSS selector, naming the configuration field on failure
+///
+/// # Arguments
+/// * `field` - Configuration field the selector comes from
+/// * `selector` - Raw CSS selector
+///
+/// # Returns
+/// * `Result<Selector>` - Parsed selector or a descriptive error
+fn parse_selector(field: &str, selector: &str) -> Result<Selector> {
+    Selector::parse(selector)
+        .map_err(|e| anyhow::anyhow!("invalid CSS selector in `{field}` ({selector:?}): {e:?}"))
+}
+
+/// Scraper that extracts listings using only the selectors in its configuration
+pub struct SelectorScraper {
+    fetcher: Fetcher,
+    config: ScraperConfig,
+    selectors: Arc<CompiledSelectors>,
+    filter: Arc<Query>,
+}
+
+impl SelectorScraper {
+    /// Create a new selector-driven scraper, validating every selector up front
+    ///
+    /// # Arguments
+    /// * `config` - Website configuration
+    /// * `limiter` - Rate limiter shared by every scraper
+    ///
+    /// # Returns
+    /// * `Result<Self>` - New scraper or an error naming the invalid selector field
+    pub fn new(config: ScraperConfig, limiter: Arc<RateLimiter>) -> Result<Self> {
+        let fetcher = Fetcher::new(&config, limiter)
+            .with_context(|| format!("Invalid configuration for scraper '{}'", config.name))?;
+
+        let selectors = CompiledSelectors::compile(&config)
+            .with_context(|| format!("Invalid configuration for scraper '{}'", config.name))?;
+        let filter = config
+            .title_filter()
+            .with_context(|| format!("Invalid configuration for scraper '{}'", config.name))?;
+
+        Ok(Self {
+            fetcher,
+            config,
+            selectors: Arc::new(selectors),
+            filter: Arc::new(filter),
+        })
+    }
+
+    /// Fetch a page and return its HTML
+    ///
+    /// # Arguments
+    /// * `url` - URL of the page
+    ///
+    /// # Returns
+    /// * `Result<String>` - The HTML, or an error for a failed request or unsuccessful status
+    async fn fetch_page(&self, url: &str) -> Result<String> {
+        let response = self.fetcher.get(url).await?;
+        if !response.status().is_success() {
+            anyhow::bail!("{}", response.status());
+        }
+
+        Ok(response.text().await?)
+    }
+
+    /// Resolve a possibly relative URL against the configured base URL
+    fn absolute_url(&self, href: &str) -> String {
+        if href.starts_with("http") {
+            href.to_string()
+        } else if href.starts_with("//") {
+            format!("https:{href}")
+        } else {
+            format!("{}{}", self.config.base_url, href)
+        }
+    }
+
+    /// Extract every matching listing from a single search results page
+    ///
+    /// # Arguments
+    /// * `document` - The parsed search results page
+    /// * `jackets` - Listings collected so far, keyed by normalized URL
+    /// * `counters` - Scrape counters to add the parsed products to
+    fn extract_jackets(&self, document: &Html, jackets: &mut HashMap<String, Jacket>, counters: &mut ScrapeStats) {
+        let selectors = &self.selectors;
+
+        for product in document.select(&selectors.product) {
+            counters.items_parsed += 1;
+
+            let Some(href) = product
+                .select(&selectors.link)
+                .next()
+                .and_then(|link| link.value().attr("href"))
+            else {
+                continue;
+            };
+
+            let mut url = self.absolute_url(href);
+
+            // Normalize URL by removing query parameters to avoid duplicates
+            if let Some(query_start) = url.find('?') {
+                url.truncate(query_start);
+            }
+
+            // Skip if we've already processed this normalized URL
+            if jackets.contains_key(&url) {
+                continue;
+            }
+
+            let product_title = product.select(&selectors.title).next().map_or_else(
+                || "Unknown Item".to_string(),
+                |el| el.text().collect::<String>().trim().to_string(),
+            );
+            if product_title.is_empty() || product_title == "Unknown Item" {
+                counters.missing_titles += 1;
+            }
+
+            let brand = selectors.brand.as_ref().and_then(|brand_sel| {
+                product
+                    .select(brand_sel)
+                    .next()
+                    .map(|el| el.text().collect::<String>().trim().to_string())
+                    .filter(|brand| !brand.is_empty())
+            });
+
+            // Combine brand and title for full item name
+            let title = match &brand {
+                Some(brand) => format!("{brand} - {product_title}"),
+                None => product_title,
+            };
+
+            // Check if this item matches the filter query
+            if !self.matches_filter(&title) {
+                continue;
+            }
+
+            // Sold out items are still reported so their lifecycle can be tracked
+            let is_sold_out = selectors.sold_out.as_ref().is_some_and(|sold_out_sel| {
+                let sold_out_text = self.config.selectors.sold_out_text.as_deref();
+                product.select(sold_out_sel).any(|el| {
+                    sold_out_text.is_none_or(|text| {
+                        el.text().collect::<String>().trim().eq_ignore_ascii_case(text)
+                    })
+                })
+            });
+            let status = if is_sold_out {
+                ListingStatus::SoldOut
+            } else {
+                ListingStatus::Available
+            };
+
+            let price = product.select(&selectors.price).next().map_or_else(
+                || Price::parse("Price not found"),
+                |el| Price::parse(&el.text().collect::<String>()),
+            );
+            if price.amount.is_none() {
+                counters.missing_prices += 1;
+            }
+
+            let image_url = product
+                .select(&selectors.image)
+                .next()
+                .and_then(|img| {
+                    // Try data-src first (for lazy loading), then src
+                    img.value()
+                        .attr("data-src")
+                        .or_else(|| img.value().attr("src"))
+                })
+                .map(|src| {
+                    // Replace {width} placeholder with fixed width for Discord display
+                    self.absolute_url(src).replace("{width}", "800")
+                });
+
+            // Generate a unique ID based on URL and website
+            let id = format!("{:x}", md5::compute(format!("{}:{}", self.config.name, url)));
+
+            let jacket = Jacket {
+                id,
+                source: self.config.name.clone(),
+                title,
+                brand,
+                price,
+                url: url.clone(),
+                image_url,
+                discovered_at: Utc::now(),
+                size: None,
+                measurements: None,
+                condition: None,
+                description: None,
+                status,
+            };
+
+            jackets.insert(url, jacket);
+        }
+    }
+}
+
+#[async_trait]
+impl WebsiteScraper for SelectorScraper {
+    fn config(&self) -> &ScraperConfig {
+        &self.config
+    }
+
+    fn filter(&self) -> &Query {
+        &self.filter
+    }
+
+    async fn search_jackets(&self, stats: &mut ScrapeStats) -> Result<SearchResults> {
+        info!(
+            "Searching for jackets on {} with {} search terms",
+            self.config.name,
+            self.config.search_terms.len()
+        );
+
+        let mut all_jackets = HashMap::new(); // For deduplication by URL
+        let mut failures = Vec::new();
+
+        for search_term in &self.config.search_terms {
+            info!("Searching for: {} on {}", search_term, self.config.name);
+
+            let mut current_url = self.build_search_url(search_term);
+            let mut page_num = 1;
+
+            // Follow pagination until no more pages
+            loop {
+                if page_num > MAX_PAGES {
+                    info!("Reached maximum page limit ({}) for search term: {} on {}",
+                          MAX_PAGES, search_term, self.config.name);
+                    break;
+                }
+
+                match self.fetcher.allows(&current_url).await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        warn!("robots.txt disallows {} - skipping page {} for search term: {} on {}",
+                              current_url, page_num, search_term, self.config.name);
+                        stats.pages_disallowed += 1;
+                        break;
+                    }
+                    Err(e) => {
+                        // Without the rules the page can't be fetched, but earlier pages are still good
+                        let failure = format!(
+                            "Failed to check robots.txt for search page {} for '{}' on {}: {}",
+                            page_num, search_term, self.config.name, e
+                        );
+                        warn!("{}", failure);
+                        failures.push(failure);
+                        break;
+                    }
+                }
+
+                info!("Fetching page {} for search term: {} on {}", page_num, search_term, self.config.name);
+
+                let html = match self.fetch_page(&current_url).await {
+                    Ok(html) => html,
+                    Err(e) => {
+                        // Keep what earlier pages and search terms found and carry on with the next term
+                        let failure = format!(
+                            "Failed to fetch search page {} for '{}' on {}: {}",
+                            page_num, search_term, self.config.name, e
+                        );
+                        warn!("{}", failure);
+                        failures.push(failure);
+                        break;
+                    }
+                };
+                stats.pages_fetched += 1;
+
+                // Process the page in a scope to ensure document is dropped before await
+                let next_page_url = {
+                    let document = Html::parse_document(&html);
+                    self.extract_jackets(&document, &mut all_jackets, stats);
+                    self.extract_next_page_url(&document)
+                }; // document is dropped here
+
+                // Check for next page
+                if let Some(next_url) = next_page_url {
+                    // Validate the next URL to prevent infinite loops on malformed pagination
+                    if next_url == current_url {
+                        info!("Next page URL is the same as current URL, stopping pagination for: {} on {}",
+                              search_term, self.config.name);
+                        break;
+                    }
+
+                    current_url = next_url;
+                    page_num += 1;
+                } else {
+                    info!("No more pages found for search term: {} on {} (searched {} pages)",
+                          search_term, self.config.name, page_num);
+                    break;
+                }
+            }
+        }
+
+        let jackets: Vec<Jacket> = all_jackets.into_values().collect();
+        stats.items_matched = u32::try_from(jackets.len()).unwrap_or(u32::MAX);
+        info!(
+            "Found {} unique jackets on {} across all search terms",
+            jackets.len(),
+            self.config.name
+        );
+
+        if stats.pages_fetched == 0 && !failures.is_empty() {
+            anyhow::bail!(failures.join("; "));
+        }
+        Ok(SearchResults { jackets, failures })
+    }
+
+    async fn enrich_jacket(&self, jacket: &mut Jacket, stats: &mut ScrapeStats) -> Result<()> {
+        let Some(details) = &self.selectors.details else {
+            return Ok(());
+        };
+
+        let allowed = self.fetcher.allows(&jacket.url).await.with_context(|| {
+            format!("Failed to check robots.txt for detail page {} on {}", jacket.url, self.config.name)
+        })?;
+        if !allowed {
+            info!("robots.txt disallows detail page {} on {} - skipping", jacket.url, self.config.name);
+            stats.pages_disallowed += 1;
+            return Ok(());
+        }
+
+        info!("Fetching detail page for {} on {}", jacket.url, self.config.name);
+
+        let response = self.fetcher.get(&jacket.url).await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Failed to fetch detail page {} on {}: {}",
+                jacket.url,
+                self.config.name,
+                response.status()
+            ));
+        }
+
+        let html = response.text().await?;
+        let document = Html::parse_document(&html);
+
+        jacket.size = select_text(&document, details.size.as_ref());
+        jacket.measurements = select_text(&document, details.measurements.as_ref());
+        jacket.condition = select_text(&document, details.condition.as_ref());
+        jacket.description = select_text(&document, details.description.as_ref());
+
+        Ok(())
+    }
+
+    fn extract_next_page_url(&self, document: &Html) -> Option<String> {
+        let pagination = document.select(&self.selectors.pagination_container).next()?;
+        let next_link = pagination.select(&self.selectors.pagination_next).next()?;
+        let href = next_link.value().attr("href")?;
+
+        // Convert relative URL to absolute URL
+        Some(self.absolute_url(href))
+    }
+}
+
+impl Clone for SelectorScraper {
+    fn clone(&self) -> Self {
+        Self {
+            fetcher: self.fetcher.clone(),
+            config: self.config.clone(),
+            selectors: self.selectors.clone(),
+            filter: self.filter.clone(),
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//! Shopify storefront scraper using the public products JSON API
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use chrono::Utc;
+use scraper::Html;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::models::{Jacket, ListingStatus, Price, ScrapeStats, SearchResults};
+use crate::query::Query;
+use crate::scrapers::{Fetcher, RateLimiter};
+use crate::traits::{RateLimit, RetryPolicy, ScraperConfig, SiteSelectors, WebsiteScraper};
+
+/// Safety limit to prevent infinite pagination loops
+const MAX_PAGES: u32 = 50;
+
+/// Maximum page size accepted by the Shopify products endpoint
+const PAGE_SIZE: u32 = 250;
+
+/// Configuration for a Shopify storefront
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShopifyConfig {
+    /// Display name for the shop (defaults to the domain)
+    pub name: Option<String>,
+    /// Shop domain, e.g. `shop.example.com`
+    pub domain: String,
+    /// Search terms matched against product titles
+    pub search_terms: Vec<String>,
+    /// Query product titles must match instead of the search terms (optional)
+    pub filter: Option<String>,
+    /// ISO 4217 currency code for prices, since the products API omits it (optional)
+    pub currency: Option<String>,
+    /// Request rate allowed against the shop (optional)
+    #[serde(default)]
+    pub rate_limit: RateLimit,
+    /// Skip robots.txt, for shops that gave permission to scrape them (optional)
+    #[serde(default)]
+    pub ignore_robots_txt: bool,
+    /// How failed requests to the shop are retried (optional)
+    #[serde(default)]
+    pub retry: RetryPolicy,
+}
+
+/// Page of products returned by `/products.json`
+#[derive(Debug, Deserialize)]
+struct ProductsPage {
+    products: Vec<ShopifyProduct>,
+}
+
+/// Product entry from the Shopify products API
+#[derive(Debug, Deserialize)]
+struct ShopifyProduct {
+    title: String,
+    handle: String,
+    #[serde(default)]
+    vendor: String,
+    #[serde(default)]
+    body_html: Option<String>,
+    #[serde(default)]
+    variants: Vec<ShopifyVariant>,
+    #[serde(default)]
+    images: Vec<ShopifyImage>,
+}
+
+/// Purchasable variant of a Shopify product
+#[derive(Debug, Deserialize)]
+struct ShopifyVariant {
+    #[serde(default)]
+    title: String,
+    price: String,
+    #[serde(default)]
+    available: bool,
+}
+
+/// Product image from the Shopify products API
+#[derive(Debug, Deserialize)]
+struct ShopifyImage {
+    src: String,
+}
+
+/// Scraper implementation for Shopify storefronts
+pub struct ShopifyScraper {
+    fetcher: Fetcher,
+    config: ScraperConfig,
+    filter: Query,
+    currency: Option<String>,
+}
+
+impl ShopifyScraper {
+    /// Create a new Shopify scraper for a shop domain
+    ///
+    /// # Arguments
+    /// * `shop` - Shop domain and search configuration
+    /// * `limiter` - Rate limiter shared by every scraper
+    ///
+    /// # Returns
+    /// * `Result<Self>` - New scraper, or an invalid configuration or HTTP client initialization error
+    pub fn new(shop: ShopifyConfig, limiter: Arc<RateLimiter>) -> Result<Self> {
+        let domain = shop
+            .domain
+            .trim_start_matches("https://")
+            .trim_start_matches("http://")
+            .trim_end_matches('/')
+            .to_string();
+        let base_url = format!("https://{domain}");
+
+        let config = ScraperConfig {
+            name: shop.name.unwrap_or_else(|| domain.clone()),
+            search_url_pattern: format!("{base_url}/search?q={{query}}"),
+            base_url,
+            selectors: SiteSelectors::default(),
+            search_terms: shop.search_terms,
+            filter: shop.filter,
+            details: None,
+            rate_limit: shop.rate_limit,
+            ignore_robots_txt: shop.ignore_robots_txt,
+            retry: shop.retry,
+        };
+        let filter = config
+            .title_filter()
+            .with_context(|| format!("Invalid configuration for scraper '{}'", config.name))?;
+
+        let fetcher = Fetcher::new(&config, limiter)
+            .with_context(|| format!("Invalid configuration for scraper '{}'", config.name))?;
+
+        Ok(Self {
+            fetcher,
+            config,
+            filter,
+            currency: shop.currency,
+        })
+    }
+
+    /// Fetch and decode one page of the products API
+    ///
+    /// # Arguments
+    /// * `url` - URL of the page
+    ///
+    /// # Returns
+    /// * `Result<ProductsPage>` - The products, or an error for a failed request or an unexpected response
+    async fn fetch_page(&self, url: &str) -> Result<ProductsPage> {
+        let response = self.fetcher.get(url).await?;
+        if !response.status().is_success() {
+            bail!("{}", response.status());
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Convert a Shopify product into a jacket listing
+    ///
+    /// # Arguments
+    /// * `product` - Product from the products API
+    /// * `counters` - Scrape counters to add missing titles and prices to
+    ///
+    /// # Returns
+    /// * `Option<Jacket>` - The listing, or `None` if it does not match the filter
+    fn product_to_jacket(&self, product: ShopifyProduct, counters: &mut ScrapeStats) -> Option<Jacket> {
+        if product.title.trim().is_empty() {
+            counters.missing_titles += 1;
+        }
+
+        let brand = Some(product.vendor.trim().to_string()).filter(|vendor| !vendor.is_empty());
+        let title = match &brand {
+            Some(brand) => format!("{} - {}", brand, product.title.trim()),
+            None => product.title.trim().to_string(),
+        };
+
+        if !self.matches_filter(&title) {
+            return None;
+        }
+
+        // Quote the cheapest variant still in stock, or the cheapest overall once sold out
+        let is_available = product.variants.iter().any(|variant| variant.available);
+        let prices: Vec<Price> = product
+            .variants
+            .iter()
+            .filter(|variant| variant.available || !is_available)
+            .map(|variant| match &self.currency {
+                Some(currency) => Price::parse(&format!("{} {currency}", variant.price)),
+                None => Price::parse(&variant.price),
+            })
+            .collect();
+        let cheapest = prices
+            .iter()
+            .filter(|price| price.amount.is_some())
+            .min_by_key(|price| price.amount)
+            .cloned();
+        let price = if let Some(price) = cheapest {
+            price
+        } else {
+            // Keep the listing with the price text as shown, like the selector scraper does
+            counters.missing_prices += 1;
+            prices.into_iter().next().unwrap_or_else(|| Price::parse("Price not found"))
+        };
+        let status = if is_available {
+            ListingStatus::Available
+        } else {
+            ListingStatus::SoldOut
+        };
+
+        // Variant titles are the sizes still in stock, unless the product has a single default variant
+        let sizes: Vec<&str> = product
+            .variants
+            .iter()
+            .filter(|variant| variant.available && variant.title != "Default Title")
+            .map(|variant| variant.title.trim())
+            .collect();
+        let size = (!sizes.is_empty()).then(|| sizes.join(", "));
+
+        let description = product
+            .body_html
+            .as_deref()
+            .map(|html| {
+                let fragment = Html::parse_fragment(html);
+                fragment.root_element().text().collect::<Vec<_>>().join(" ")
+            })
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|text| !text.is_empty());
+
+        let url = format!("{}/products/{}", self.config.base_url, product.handle);
+        let image_url = product.images.into_iter().next().map(|image| {
+            if image.src.starts_with("//") {
+                format!("https:{}", image.src)
+            } else {
+                image.src
+            }
+        });
+
+        // Generate a unique ID based on URL and website
+        let id = format!("{:x}", md5::compute(format!("{}:{}", self.config.name, url)));
+
+        Some(Jacket {
+            id,
+            source: self.config.name.clone(),
+            title,
+            brand,
+            price,
+            url,
+            image_url,
+            discovered_at: Utc::now(),
+            size,
+            measurements: None,
+            condition: None,
+            description,
+            status,
+        })
+    }
+}
+
+#[async_trait]
+impl WebsiteScraper for ShopifyScraper {
+    fn config(&self) -> &ScraperConfig {
+        &self.config
+    }
+
+    fn filter(&self) -> &Query {
+        &self.filter
+    }
+
+    async fn search_jackets(&self, stats: &mut ScrapeStats) -> Result<SearchResults> {
+        info!(
+            "Searching for jackets on {} with {} search terms",
+            self.config.name,
+            self.config.search_terms.len()
+        );
+
+        let mut all_jackets = HashMap::new(); // For deduplication by URL
+        let mut failures = Vec::new();
+
+        for page_num in 1..=MAX_PAGES {
+            let url = format!(
+                "{}/products.json?limit={PAGE_SIZE}&page={page_num}",
+                self.config.base_url
+            );
+            match self.fetcher.allows(&url).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    warn!("robots.txt disallows {} - not searching {}", url, self.config.name);
+                    stats.pages_disallowed += 1;
+                    break;
+                }
+                Err(e) => {
+                    // Without the rules the page can't be fetched, but earlier pages are still good
+                    let failure = format!(
+                        "Failed to check robots.txt for products page {} on {}: {}",
+                        page_num, self.config.name, e
+                    );
+                    warn!("{}", failure);
+                    failures.push(failure);
+                    break;
+                }
+            }
+
+            info!("Fetching products page {} on {}", page_num, self.config.name);
+            let page = match self.fetch_page(&url).await {
+                Ok(page) => page,
+                Err(e) => {
+                    // Keep the products of earlier pages
+                    let failure = format!("Failed to fetch products page {} on {}: {}", page_num, self.config.name, e);
+                    warn!("{}", failure);
+                    failures.push(failure);
+                    break;
+                }
+            };
+            stats.pages_fetched += 1;
+            stats.items_parsed += u32::try_from(page.products.len()).unwrap_or(u32::MAX);
+            if page.products.is_empty() {
+                info!("No more products on {} (searched {} pages)", self.config.name, page_num);
+                break;
+            }
+
+            let is_last_page = page.products.len() < PAGE_SIZE as usize;
+            for product in page.products {
+                if let Some(jacket) = self.product_to_jacket(product, stats) {
+                    all_jackets.entry(jacket.url.clone()).or_insert(jacket);
+                }
+            }
+
+            if is_last_page {
+                info!("No more products on {} (searched {} pages)", self.config.name, page_num);
+                break;
+            }
+        }
+
+        let jackets: Vec<Jacket> = all_jackets.into_values().collect();
+        stats.items_matched = u32::try_from(jackets.len()).unwrap_or(u32::MAX);
+        info!(
+            "Found {} unique jackets on {} across all search terms",
+            jackets.len(),
+            self.config.name
+        );
+
+        if stats.pages_fetched == 0 && !failures.is_empty() {
+            bail!(failures.join("; "));
+        }
+        Ok(SearchResults { jackets, failures })
+    }
+
+    fn extract_next_page_url(&self, _document: &Html) -> Option<String> {
+        // The products API is paginated by page number, not HTML links
+        None
+    }
+}
+
+impl Clone for ShopifyScraper {
+    fn clone(&self) -> Self {
+        Self {
+            fetcher: self.fetcher.clone(),
+            config: self.config.clone(),
+            filter: self.filter.clone(),
+            currency: self.currency.clone(),
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Named saved searches filtering which listings are worth a notification
+
+use anyhow::{Context, Result, bail};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::models::{Jacket, Price};
+use crate::notifiers::Destination;
+use crate::query::contains_phrase;
+
+/// Default file holding saved search definitions
+pub const DEFAULT_SEARCHES_FILE: &str = "searches.toml";
+
+/// A named set of filters a listing must pass to be announced
+///
+/// Every filter is optional; an empty list or unset bound accepts every listing.
+/// Text comparisons are case-insensitive, and keywords match whole words like title filters.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedSearch {
+    /// Unique display name, e.g. "N-1 size 40 under €300"
+    pub name: String,
+    /// Words or phrases that must all appear in the title as whole words
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Words or phrases that must not appear in the title as whole words
+    #[serde(default)]
+    pub exclude_keywords: Vec<String>,
+    /// Brands to accept; any one of them must match
+    #[serde(default)]
+    pub brands: Vec<String>,
+    /// Brands to reject
+    #[serde(default)]
+    pub exclude_brands: Vec<String>,
+    /// Lowest accepted price (inclusive)
+    pub min_price: Option<Decimal>,
+    /// Highest accepted price (inclusive)
+    pub max_price: Option<Decimal>,
+    /// ISO 4217 currency the price bounds are in; listings in other currencies are rejected
+    pub currency: Option<String>,
+    /// Sizes to accept; any one of them must match
+    #[serde(default)]
+    pub sizes: Vec<String>,
+    /// Scraper names to accept; any one of them must match
+    #[serde(default)]
+    pub sites: Vec<String>,
+    /// Where to send matching listings; empty uses the notifiers configured in the environment
+    #[serde(default)]
+    pub notify: Vec<Destination>,
+}
+
+impl SavedSearch {
+    /// Check whether a listing passes every filter of this search
+    ///
+    /// # Arguments
+    /// * `jacket` - The listing to check
+    ///
+    /// # Returns
+    /// * `bool` - `true` if the listing matches
+    pub fn matches(&self, jacket: &Jacket) -> bool {
+        self.keywords.iter().all(|keyword| contains_phrase(&jacket.title, keyword))
+            && !self.exclude_keywords.iter().any(|keyword| contains_phrase(&jacket.title, keyword))
+            && (self.brands.is_empty() || self.brands.iter().any(|brand| has_brand(jacket, brand)))
+            && !self.exclude_brands.iter().any(|brand| has_brand(jacket, brand))
+            && self.matches_price(&jacket.price)
+            && (self.sizes.is_empty() || self.sizes.iter().any(|size| has_size(jacket, size)))
+            && (self.sites.is_empty() || self.sites.iter().any(|site| site.eq_ignore_ascii_case(&jacket.source)))
+    }
+
+    /// Check a price against the price range, rejecting unparsed prices when a bound is set
+    fn matches_price(&self, price: &Price) -> bool {
+        if self.min_price.is_none() && self.max_price.is_none() {
+            return true;
+        }
+
+        let Some(amount) = price.amount else {
+            return false;
+        };
+        if let (Some(wanted), Some(currency)) = (&self.currency, &price.currency)
+            && !wanted.eq_ignore_ascii_case(currency)
+        {
+            return false;
+        }
+
+        self.min_price.is_none_or(|min| amount >= min) && self.max_price.is_none_or(|max| amount <= max)
+    }
+
+    /// Reject searches that can never match or would be ambiguous
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("Saved search names must not be empty");
+        }
+        if let (Some(min), Some(max)) = (self.min_price, self.max_price)
+            && min > max
+        {
+            bail!("Saved search '{}' has min_price {} above max_price {}", self.name, min, max);
+        }
+        if let Some(keyword) = self
+            .keywords
+            .iter()
+            .chain(&self.exclude_keywords)
+            .find(|keyword| !keyword.chars().any(char::is_alphanumeric))
+        {
+            bail!("Saved search '{}' has keyword {:?} without letters or digits", self.name, keyword);
+        }
+        Ok(())
+    }
+}
+
+/// Check a listing's brand, falling back to its title when the scraper found no brand
+///
+/// In the title the brand must appear as whole words, so `Lee` does not match "Fleece".
+fn has_brand(jacket: &Jacket, brand: &str) -> bool {
+    match &jacket.brand {
+        Some(listed) => listed.eq_ignore_ascii_case(brand.trim()),
+        None => contains_phrase(&jacket.title, brand),
+    }
+}
+
+/// Check a listing's sizes, falling back to its title when the size is unknown
+///
+/// Sizes are compared as whole words, so `M` does not match `XM` and `40` does not match `140`.
+fn has_size(jacket: &Jacket, size: &str) -> bool {
+    let text = jacket.size.as_deref().unwrap_or(&jacket.title);
+    text.split(|c: char| !c.is_alphanumeric() && c != '.')
+        .any(|word| word.eq_ignore_ascii_case(size.trim()))
+}
+
+/// Saved searches a listing matches
+///
+/// # Arguments
+/// * `searches` - Saved searches to evaluate
+/// * `jacket` - The listing to check
+///
+/// # Returns
+/// * `Vec<&SavedSearch>` - The matching searches, in definition order
+pub fn matching_searches<'a>(searches: &'a [SavedSearch], jacket: &Jacket) -> Vec<&'a SavedSearch> {
+    searches.iter().filter(|search| search.matches(jacket)).collect()
+}
+
+/// Saved search definition file contents
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SearchesFile {
+    #[serde(default)]
+    search: Vec<SavedSearch>,
+}
+
+/// Get the saved searches file from the environment
+///
+/// # Returns
+/// * `PathBuf` - Value of `SEARCHES_FILE`, or `searches.toml` when unset
+pub fn searches_file() -> PathBuf {
+    std::env::var("SEARCHES_FILE").map_or_else(|_| PathBuf::from(DEFAULT_SEARCHES_FILE), PathBuf::from)
+}
+
+/// Load the saved searches defined in a TOML file
+///
+/// # Arguments
+/// * `path` - File with one `[[search]]` table per saved search
+///
+/// # Returns
+/// * `Result<Option<Vec<SavedSearch>>>` - The searches, `None` if the file does not exist,
+///   or a parse/validation error
+pub fn load_searches(path: &Path) -> Result<Option<Vec<SavedSearch>>> {
+    if !path.is_file() {
+        info!("No saved searches file at {}", path.display());
+        return Ok(None);
+    }
+
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read saved searches file {}", path.display()))?;
+    let file: SearchesFile = toml::from_str(&contents)
+        .with_context(|| format!("Invalid saved searches file {}", path.display()))?;
+
+    let mut names = HashSet::new();
+    for search in &file.search {
+        search.validate()?;
+        if !names.insert(search.name.to_lowercase()) {
+            bail!("Duplicate saved search name '{}' - saved search names must be unique", search.name);
+        }
+    }
+
+    info!("Loaded {} saved searches from {}", file.search.len(), path.display());
+    Ok(Some(file.search))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ListingStatus;
+    use chrono::Utc;
+
+    /// Available listing with a title and nothing else known
+    fn jacket(title: &str) -> Jacket {
+        Jacket {
+            id: "test".to_string(),
+            source: "Test".to_string(),
+            title: title.to_string(),
+            brand: None,
+            price: Price::parse("€ 250,00"),
+            url: "https://example.com/jacket".to_string(),
+            image_url: None,
+            discovered_at: Utc::now(),
+            size: None,
+            measurements: None,
+            condition: None,
+            description: None,
+            status: ListingStatus::Available,
+        }
+    }
+
+    #[test]
+    fn keywords_match_whole_words() {
+        let search = SavedSearch {
+            name: "N-1".to_string(),
+            keywords: vec!["n-1".to_string()],
+            ..SavedSearch::default()
+        };
+        assert!(search.matches(&jacket("Buzz Rickson's N-1 Deck Jacket")));
+        assert!(!search.matches(&jacket("Buzz Rickson's N-10 Deck Jacket")));
+    }
+
+    #[test]
+    fn exclude_keywords_match_whole_words() {
+        let search = SavedSearch {
+            name: "No repros".to_string(),
+            exclude_keywords: vec!["repro".to_string()],
+            ..SavedSearch::default()
+        };
+        assert!(!search.matches(&jacket("N-1 Deck Jacket Repro")));
+        assert!(search.matches(&jacket("Reproduction-grade N-1 Deck Jacket")));
+    }
+
+    #[test]
+    fn brands_in_titles_match_whole_words() {
+        let search = SavedSearch {
+            name: "Lee".to_string(),
+            brands: vec!["Lee".to_string()],
+            ..SavedSearch::default()
+        };
+        assert!(search.matches(&jacket("Lee Storm Rider Jacket")));
+        assert!(!search.matches(&jacket("Navy Fleece Deck Jacket")));
+
+        let search = SavedSearch {
+            name: "No Lee".to_string(),
+            exclude_brands: vec!["Lee".to_string()],
+            ..SavedSearch::default()
+        };
+        assert!(search.matches(&jacket("Navy Fleece Deck Jacket")));
+    }
+
+    #[test]
+    fn keywords_without_letters_or_digits_are_rejected() {
+        let search = SavedSearch {
+            name: "Dashes".to_string(),
+            exclude_keywords: vec!["--".to_string()],
+            ..SavedSearch::default()
+        };
+        assert!(search.validate().is_err());
+    }
+}
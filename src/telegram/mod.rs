@@ -0,0 +1,386 @@
+//! Telegram Bot API notifications for jacket discoveries
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::models::{
+    Jacket, NotificationEvent, TelegramInlineButton, TelegramMessage, TelegramPhoto,
+    TelegramReplyMarkup, TelegramResponse,
+};
+use crate::notifiers::{Destination, escape_html, truncate};
+use crate::traits::Notifier;
+
+/// Default Telegram Bot API base URL
+const DEFAULT_API_BASE_URL: &str = "https://api.telegram.org";
+
+/// Maximum length of a photo caption accepted by Telegram
+const CAPTION_LIMIT: usize = 1024;
+
+/// Maximum length of a message text accepted by Telegram
+const MESSAGE_LIMIT: usize = 4096;
+
+/// Telegram bot client for sending jacket notifications to a chat
+pub struct TelegramNotifier {
+    client: Client,
+    api_base_url: String,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl TelegramNotifier {
+    /// Create a new Telegram notifier
+    ///
+    /// # Arguments
+    /// * `bot_token` - Bot token issued by `@BotFather`
+    /// * `chat_id` - Target chat ID or `@channel` username
+    ///
+    /// # Returns
+    /// * `Self` - New `TelegramNotifier` talking to the public Bot API
+    pub fn new(bot_token: String, chat_id: String) -> Self {
+        Self {
+            client: Client::new(),
+            api_base_url: DEFAULT_API_BASE_URL.to_string(),
+            bot_token,
+            chat_id,
+        }
+    }
+
+    /// Use a different Bot API base URL, e.g. a local stand-in for testing
+    ///
+    /// # Arguments
+    /// * `api_base_url` - Base URL without trailing slash
+    ///
+    /// # Returns
+    /// * `Self` - The notifier using the given API base URL
+    pub fn with_api_base_url(mut self, api_base_url: &str) -> Self {
+        self.api_base_url = api_base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Create a Telegram notifier from environment configuration
+    ///
+    /// # Returns
+    /// * `Option<Self>` - Notifier for `TELEGRAM_BOT_TOKEN` and `TELEGRAM_CHAT_ID`, or `None` if unset
+    pub fn from_env() -> Option<Self> {
+        let bot_token = std::env::var("TELEGRAM_BOT_TOKEN").ok();
+        let chat_id = std::env::var("TELEGRAM_CHAT_ID").ok();
+
+        let (Some(bot_token), Some(chat_id)) = (bot_token, chat_id) else {
+            // A token on its own is only used by saved searches with Telegram destinations
+            if std::env::var_os("TELEGRAM_CHAT_ID").is_some() {
+                warn!("TELEGRAM_CHAT_ID is set without TELEGRAM_BOT_TOKEN - Telegram notifications will be disabled");
+            }
+            return None;
+        };
+
+        Some(Self::new(bot_token, chat_id).with_env_api_base_url())
+    }
+
+    /// Create a Telegram notifier for a specific chat, using the bot from the environment
+    ///
+    /// # Arguments
+    /// * `chat_id` - Target chat ID or `@channel` username
+    ///
+    /// # Returns
+    /// * `Result<Self>` - Notifier using `TELEGRAM_BOT_TOKEN`, or an error if it is not set
+    pub fn for_chat(chat_id: &str) -> Result<Self> {
+        let bot_token = std::env::var("TELEGRAM_BOT_TOKEN")
+            .context("TELEGRAM_BOT_TOKEN must be set to send Telegram notifications")?;
+
+        Ok(Self::new(bot_token, chat_id.to_string()).with_env_api_base_url())
+    }
+
+    /// Apply `TELEGRAM_API_BASE_URL` when it is set
+    fn with_env_api_base_url(self) -> Self {
+        match std::env::var("TELEGRAM_API_BASE_URL") {
+            Ok(api_base_url) => self.with_api_base_url(&api_base_url),
+            Err(_) => self,
+        }
+    }
+
+    /// Send a listing with its image, falling back to a text message without one
+    ///
+    /// # Arguments
+    /// * `jacket` - The listing to send
+    /// * `headline` - First line of the message, e.g. "New jacket found"
+    /// * `extra_lines` - Additional lines shown below the price
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success or network/API error
+    async fn send_listing(&self, jacket: &Jacket, headline: &str, extra_lines: &[String]) -> Result<()> {
+        let mut lines = vec![jacket.title.clone(), format!("💰 {}", jacket.price)];
+        lines.extend_from_slice(extra_lines);
+
+        let reply_markup = Some(TelegramReplyMarkup {
+            inline_keyboard: vec![vec![TelegramInlineButton {
+                text: format!("View on {}", jacket.source),
+                url: jacket.url.clone(),
+            }]],
+        });
+
+        if let Some(image_url) = &jacket.image_url {
+            let photo = TelegramPhoto {
+                chat_id: self.chat_id.clone(),
+                photo: image_url.clone(),
+                caption: html_text(headline, &lines, CAPTION_LIMIT),
+                parse_mode: "HTML",
+                reply_markup: reply_markup.clone(),
+            };
+
+            match self.call("sendPhoto", &photo).await {
+                Ok(()) => {
+                    info!("Telegram notification sent for: {}", jacket.title);
+                    return Ok(());
+                }
+                // Telegram fetches the photo itself and rejects URLs it cannot load
+                Err(e) => warn!("Telegram rejected photo for {}, sending text only: {}", jacket.title, e),
+            }
+        }
+
+        let message = TelegramMessage {
+            chat_id: self.chat_id.clone(),
+            text: html_text(headline, &lines, MESSAGE_LIMIT),
+            parse_mode: "HTML",
+            reply_markup,
+        };
+        self.call("sendMessage", &message).await?;
+
+        info!("Telegram notification sent for: {}", jacket.title);
+        Ok(())
+    }
+
+    /// Call a Bot API method
+    ///
+    /// # Arguments
+    /// * `method` - API method name, e.g. `sendMessage`
+    /// * `payload` - JSON request body
+    ///
+    /// # Returns
+    /// * `Result<()>` - Success, or a network error or API error description
+    async fn call<T: Serialize + Sync>(&self, method: &str, payload: &T) -> Result<()> {
+        let url = format!("{}/bot{}/{}", self.api_base_url, self.bot_token, method);
+        let response = self.client.post(&url).json(payload).send().await?;
+        let status = response.status();
+
+        let body: Option<TelegramResponse> = response.json().await.ok();
+        match body {
+            Some(body) if body.ok => Ok(()),
+            Some(body) => bail!(
+                "Telegram {} failed ({}): {}",
+                method,
+                status,
+                body.description.unwrap_or_default()
+            ),
+            None => bail!("Telegram {} failed: {}", method, status),
+        }
+    }
+}
+
+/// Format a message in Telegram's HTML parse mode, with a bold headline above plain lines
+///
+/// Telegram limits the visible text rather than the markup, so the plain text is cut to the
+/// limit before it is escaped; a cut never splits an entity or leaves a tag open.
+///
+/// # Arguments
+/// * `headline` - First line, shown in bold
+/// * `lines` - Lines below the headline
+/// * `limit` - Maximum number of visible characters, including the line breaks
+///
+/// # Returns
+/// * `String` - The message text, marked with an ellipsis if it was cut
+fn html_text(headline: &str, lines: &[String], limit: usize) -> String {
+    let mut text = headline.to_string();
+    for line in lines {
+        text.push('\n');
+        text.push_str(line);
+    }
+    let text = truncate(&text, limit);
+
+    // Whatever is left of the headline after the cut is still shown in bold
+    let split = text
+        .char_indices()
+        .nth(headline.chars().count())
+        .map_or(text.len(), |(index, _)| index);
+    let (headline, rest) = text.split_at(split);
+    format!("<b>{}</b>{}", escape_html(headline), escape_html(rest))
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &'static str {
+        "Telegram"
+    }
+
+    fn route(&self) -> String {
+        Destination::Telegram(self.chat_id.clone()).key()
+    }
+
+    async fn notify(&self, event: &NotificationEvent) -> Result<()> {
+        match event {
+            NotificationEvent::NewListing(jacket) => {
+                let details: Vec<String> = [
+                    ("📏 Size", &jacket.size),
+                    ("✨ Condition", &jacket.condition),
+                ]
+                .into_iter()
+                .filter_map(|(label, value)| value.as_ref().map(|value| format!("{label}: {value}")))
+                .collect();
+
+                self.send_listing(jacket, "🧥 New jacket found", &details).await
+            }
+            NotificationEvent::PriceDrop(drop) => {
+                let lines = [format!("Was {} (-{}%)", drop.old_price, drop.percent)];
+                self.send_listing(&drop.jacket, "📉 Price dropped", &lines).await
+            }
+            NotificationEvent::BackInStock(jacket) => {
+                self.send_listing(jacket, "🔁 Back in stock", &[]).await
+            }
+            NotificationEvent::Error { context, message } => {
+                let message = TelegramMessage {
+                    chat_id: self.chat_id.clone(),
+                    text: html_text(&format!("⚠️ Error: {context}"), std::slice::from_ref(message), MESSAGE_LIMIT),
+                    parse_mode: "HTML",
+                    reply_markup: None,
+                };
+                self.call("sendMessage", &message).await
+            }
+            NotificationEvent::ScraperUnhealthy { scraper, problems } => {
+                let lines: Vec<String> = problems.iter().map(|problem| format!("• {problem}")).collect();
+                let message = TelegramMessage {
+                    chat_id: self.chat_id.clone(),
+                    text: html_text(&format!("🩺 Scraper unhealthy: {scraper}"), &lines, MESSAGE_LIMIT),
+                    parse_mode: "HTML",
+                    reply_markup: None,
+                };
+                self.call("sendMessage", &message).await
+            }
+        }
+    }
+}
+
+impl Clone for TelegramNotifier {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+            api_base_url: self.api_base_url.clone(),
+            bot_token: self.bot_token.clone(),
+            chat_id: self.chat_id.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ListingStatus, Price};
+    use chrono::Utc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Listing without an image, so it is sent as a text message
+    fn jacket(title: &str) -> Jacket {
+        Jacket {
+            id: "test".to_string(),
+            source: "Test".to_string(),
+            title: title.to_string(),
+            brand: None,
+            price: Price::parse("€ 250,00"),
+            url: "https://example.com/jacket".to_string(),
+            image_url: None,
+            discovered_at: Utc::now(),
+            size: Some("40".to_string()),
+            measurements: None,
+            condition: None,
+            description: None,
+            status: ListingStatus::Available,
+        }
+    }
+
+    /// Answer one Bot API request with success, returning its request line and JSON body
+    async fn serve_one(listener: TcpListener) -> (String, serde_json::Value) {
+        let (mut stream, _) = listener.accept().await.expect("connection");
+        let mut request = Vec::new();
+        let mut buffer = [0; 4096];
+        loop {
+            let read = stream.read(&mut buffer).await.expect("request");
+            request.extend_from_slice(&buffer[..read]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length = head
+                    .lines()
+                    .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(str::to_string))
+                    .and_then(|length| length.trim().parse::<usize>().ok())
+                    .unwrap_or_default();
+                if body.len() >= length || read == 0 {
+                    break;
+                }
+            }
+        }
+
+        let response = r#"{"ok":true}"#;
+        let reply = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+            response.len()
+        );
+        stream.write_all(reply.as_bytes()).await.expect("response");
+
+        let text = String::from_utf8_lossy(&request);
+        let (head, body) = text.split_once("\r\n\r\n").expect("complete request");
+        let request_line = head.lines().next().unwrap_or_default().to_string();
+        (request_line, serde_json::from_str(body).expect("JSON body"))
+    }
+
+    #[test]
+    fn html_text_escapes_and_bolds_the_headline() {
+        let lines = ["Barbour <Bedale> & co".to_string(), "💰 €250.00".to_string()];
+        assert_eq!(
+            html_text("New jacket", &lines, CAPTION_LIMIT),
+            "<b>New jacket</b>\nBarbour &lt;Bedale&gt; &amp; co\n💰 €250.00"
+        );
+    }
+
+    #[test]
+    fn html_text_cuts_the_plain_text_before_escaping() {
+        // The cut lands right after the ampersand, which must stay a whole entity
+        let lines = ["a & b <c>".to_string()];
+        assert_eq!(html_text("New", &lines, 8), "<b>New</b>\na &amp;…");
+
+        // A cut inside the headline keeps the bold tag closed
+        assert_eq!(html_text("Headline", &lines, 4), "<b>Hea…</b>");
+    }
+
+    #[test]
+    fn html_text_limit_counts_visible_characters() {
+        let lines = ["&".repeat(CAPTION_LIMIT)];
+        let text = html_text("New", &lines, CAPTION_LIMIT);
+        let visible = text.replace("<b>", "").replace("</b>", "").replace("&amp;", "&");
+        assert_eq!(visible.chars().count(), CAPTION_LIMIT);
+        assert!(visible.ends_with('…'));
+    }
+
+    #[tokio::test]
+    async fn sends_listings_to_the_configured_api_base_url() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("listener");
+        let base_url = format!("http://{}/", listener.local_addr().expect("address"));
+        let server = tokio::spawn(serve_one(listener));
+
+        let notifier = TelegramNotifier::new("token".to_string(), "42".to_string()).with_api_base_url(&base_url);
+        notifier
+            .notify(&NotificationEvent::NewListing(jacket("N-1 <Deck> Jacket")))
+            .await
+            .expect("sent");
+
+        let (request_line, body) = server.await.expect("stand-in");
+        assert_eq!(request_line, "POST /bottoken/sendMessage HTTP/1.1");
+        assert_eq!(body["chat_id"], "42");
+        assert_eq!(body["parse_mode"], "HTML");
+        assert_eq!(
+            body["text"],
+            format!("<b>🧥 New jacket found</b>\nN-1 &lt;Deck&gt; Jacket\n💰 {}\n📏 Size: 40", jacket("").price)
+        );
+        assert_eq!(body["reply_markup"]["inline_keyboard"][0][0]["url"], "https://example.com/jacket");
+    }
+}
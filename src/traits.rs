@@ -1,12 +1,19 @@
-//! Traits and interfaces for website-agnostic scraping
+//! Traits and interfaces for website-agnostic scraping, storage and notification delivery
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
 
-use crate::models::Jacket;
+use crate::database::{DatabaseStats, KnownListing, ListingFilter, OutboxEntry};
+use crate::models::{Jacket, NotificationEvent, ScrapeRun, ScrapeStats, ScraperRun, SearchResults};
+use crate::query::Query;
+use crate::searches::SavedSearch;
 
 /// Configuration for a website scraper
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct ScraperConfig {
     /// Display name for the website
     pub name: String,
@@ -18,10 +25,86 @@ pub struct ScraperConfig {
     pub selectors: SiteSelectors,
     /// Search terms specific to this website
     pub search_terms: Vec<String>,
+    /// Query listing titles must match, e.g. `N-1 AND (navy OR khaki) NOT repro` (optional)
+    ///
+    /// When unset, a title must contain one of the search terms as whole words.
+    pub filter: Option<String>,
+    /// CSS selectors for the product detail page, enabling the enrichment pass (optional)
+    pub details: Option<DetailSelectors>,
+    /// Request rate allowed against the website's host (optional)
+    #[serde(default)]
+    pub rate_limit: RateLimit,
+    /// Skip robots.txt, for websites that gave permission to scrape them (optional)
+    #[serde(default)]
+    pub ignore_robots_txt: bool,
+    /// How failed requests to the website are retried (optional)
+    #[serde(default)]
+    pub retry: RetryPolicy,
+}
+
+impl ScraperConfig {
+    /// Parse the title filter, defaulting to any of the search terms
+    /// 
+    /// # Returns
+    /// * `Result<Query>` - The filter query or a parse error naming the `filter` field
+    pub fn title_filter(&self) -> Result<Query> {
+        match &self.filter {
+            Some(filter) => Query::parse(filter).with_context(|| format!("invalid `filter` ({filter:?})")),
+            None => Query::any_phrase(&self.search_terms).context("invalid `search_terms`"),
+        }
+    }
+}
+
+/// Request rate a scraper may use against one host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimit {
+    /// Sustained number of requests per minute
+    pub requests_per_minute: u32,
+    /// Requests that may be sent back to back before the sustained rate applies
+    pub burst: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 30,
+            burst: 3,
+        }
+    }
+}
+
+/// How a scraper retries requests that failed with a network error or a retryable status
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryPolicy {
+    /// Attempts per request, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry in milliseconds, doubled after every further failure
+    pub initial_backoff_ms: u64,
+    /// Longest delay between attempts in milliseconds; a longer `Retry-After` gives up instead
+    pub max_backoff_ms: u64,
+    /// Fraction of each delay that is randomized, from `0` (none) to `1` (anywhere down to zero)
+    pub jitter: f64,
+    /// Response statuses worth retrying
+    pub retry_statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 30_000,
+            jitter: 0.5,
+            retry_statuses: vec![408, 429, 500, 502, 503, 504],
+        }
+    }
 }
 
 /// CSS selectors for different parts of a product listing
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct SiteSelectors {
     /// Container selector for individual products
     pub product_container: String,
@@ -41,6 +124,24 @@ pub struct SiteSelectors {
     pub pagination_next: String,
     /// Sold out indicator selector (optional)
     pub sold_out_indicator: Option<String>,
+    /// Text the sold out indicator must read to mark an item as sold out (optional)
+    ///
+    /// When unset, any element matching `sold_out_indicator` marks the item as sold out.
+    pub sold_out_text: Option<String>,
+}
+
+/// CSS selectors for fields only available on a product detail page
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DetailSelectors {
+    /// Size selector (optional)
+    pub size: Option<String>,
+    /// Measurements selector, every match becomes one line (optional)
+    pub measurements: Option<String>,
+    /// Condition selector (optional)
+    pub condition: Option<String>,
+    /// Description selector (optional)
+    pub description: Option<String>,
 }
 
 /// Trait for website-specific scrapers
@@ -51,9 +152,32 @@ pub trait WebsiteScraper: Send + Sync {
     
     /// Search for jackets on this website
     /// 
+    /// A page that cannot be fetched does not discard what earlier pages and search terms
+    /// found; it is reported among the failures of the results instead.
+    /// 
+    /// # Arguments
+    /// * `stats` - Counters updated as pages are fetched and parsed, kept even if the search fails
+    /// 
     /// # Returns
-    /// * `Result<Vec<Jacket>>` - Vector of found jackets or scraping error
-    async fn search_jackets(&self) -> Result<Vec<Jacket>>;
+    /// * `Result<SearchResults>` - Found jackets with any page failures, or an error if nothing
+    ///   could be searched at all
+    async fn search_jackets(&self, stats: &mut ScrapeStats) -> Result<SearchResults>;
+    
+    /// Fill in detail fields for a newly discovered jacket from its product page
+    /// 
+    /// Only called for listings that have not been seen before. A product page that
+    /// robots.txt disallows is skipped and counted, leaving the jacket untouched. The
+    /// default implementation leaves the jacket untouched.
+    /// 
+    /// # Arguments
+    /// * `jacket` - The jacket to enrich in place
+    /// * `stats` - Counters of the scraper's search, updated with skipped pages
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Success or fetch/parse error
+    async fn enrich_jacket(&self, _jacket: &mut Jacket, _stats: &mut ScrapeStats) -> Result<()> {
+        Ok(())
+    }
     
     /// Extract the next page URL from pagination HTML
     /// 
@@ -76,9 +200,224 @@ pub trait WebsiteScraper: Send + Sync {
         self.config().search_url_pattern.replace("{query}", &encoded_term)
     }
     
+    /// Get the query listing titles must match
+    fn filter(&self) -> &Query;
+
+    /// Check whether a listing title matches this scraper's filter
+    /// 
+    /// # Arguments
+    /// * `title` - The full listing title, including brand
+    /// 
+    /// # Returns
+    /// * `bool` - True if the title matches the filter query
+    fn matches_filter(&self, title: &str) -> bool {
+        self.filter().matches(title)
+    }
+    
     /// Get the user agent string for HTTP requests
     #[allow(dead_code)]
     fn user_agent(&self) -> &'static str {
-        "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36"
+        crate::scrapers::fetcher::USER_AGENT
+    }
+}
+
+/// Trait for notification backends
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Get the display name of this backend, used in logs
+    fn name(&self) -> &str;
+
+    /// Get the key routing queued notifications to this notifier
+    /// 
+    /// Equals the `Destination::key` of where it delivers, so a saved search destination
+    /// pointing at the same place shares its route.
+    fn route(&self) -> String;
+
+    /// Deliver a single event
+    /// 
+    /// # Arguments
+    /// * `event` - The event to deliver
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Success, or an error if the event was not delivered
+    async fn notify(&self, event: &NotificationEvent) -> Result<()>;
+
+    /// Deliver every event produced by one check
+    /// 
+    /// The default implementation sends each event on its own; digest-style
+    /// backends override it to send a single message.
+    /// 
+    /// # Arguments
+    /// * `events` - Events from one check, in discovery order
+    /// 
+    /// # Returns
+    /// * `Vec<Result<()>>` - One result per event, in the same order
+    async fn notify_batch(&self, events: &[NotificationEvent]) -> Vec<Result<()>> {
+        let mut results = Vec::with_capacity(events.len());
+        for event in events {
+            results.push(self.notify(event).await);
+        }
+        results
     }
 }
+
+/// Trait for storage backends holding listings, saved searches and queued notifications
+///
+/// Several finder instances may share one store, so every write that decides whether a
+/// notification is sent must be safe against another instance doing the same at once.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Get the website, current price and status of every known jacket, keyed by jacket ID
+    /// 
+    /// The keys double as the set of existing jacket IDs for duplicate checking.
+    /// 
+    /// # Returns
+    /// * `Result<HashMap<String, KnownListing>>` - Known listings by jacket ID or database error
+    async fn get_known_listings(&self) -> Result<HashMap<String, KnownListing>>;
+
+    /// Save a new jacket, queueing its notification in the same transaction
+    /// 
+    /// Nothing is written if the jacket is already stored, e.g. because another instance
+    /// found it first.
+    /// 
+    /// # Arguments
+    /// * `jacket` - Reference to the jacket to save
+    /// * `event` - Notification to queue for the jacket, if any
+    /// * `notifiers` - Routing keys of the notifiers that should deliver the notification
+    /// 
+    /// # Returns
+    /// * `Result<bool>` - Whether the jacket was saved, or database insertion error
+    async fn save_jacket(&self, jacket: &Jacket, event: Option<&NotificationEvent>, notifiers: &[String])
+    -> Result<bool>;
+
+    /// Record that a known jacket was seen again, queueing any notification in the same transaction
+    /// 
+    /// Updates the status, `last_seen` and current price and appends the price to the
+    /// history. `sold_at` is set when the listing first turns sold out and cleared when it
+    /// becomes available again.
+    /// 
+    /// # Arguments
+    /// * `jacket` - The jacket as just scraped
+    /// * `event` - Notification caused by the sighting, e.g. a price drop, if any
+    /// * `notifiers` - Routing keys of the notifiers that should deliver the notification
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Success or database error
+    async fn record_sighting(
+        &self,
+        jacket: &Jacket,
+        event: Option<&NotificationEvent>,
+        notifiers: &[String],
+    ) -> Result<()>;
+
+    /// Queue a notification that is not tied to a listing change, such as an error
+    /// 
+    /// # Arguments
+    /// * `event` - The notification to queue
+    /// * `notifiers` - Routing keys of the notifiers that should deliver it
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Success or database error
+    async fn enqueue(&self, event: &NotificationEvent, notifiers: &[String]) -> Result<()>;
+
+    /// Claim every pending outbox entry whose next attempt is due, oldest first
+    /// 
+    /// Claimed entries are not due again until `lease_until`, so no other worker delivers
+    /// them in the meantime; an entry whose worker dies is retried once the lease runs out.
+    /// 
+    /// # Arguments
+    /// * `now` - Current time
+    /// * `lease_until` - When claimed entries become due again unless marked delivered or failed
+    /// 
+    /// # Returns
+    /// * `Result<Vec<OutboxEntry>>` - Claimed entries or database error
+    async fn claim_outbox_entries(&self, now: DateTime<Utc>, lease_until: DateTime<Utc>) -> Result<Vec<OutboxEntry>>;
+
+    /// Mark an outbox entry as delivered, recording when its listing was first announced
+    /// 
+    /// # Arguments
+    /// * `entry` - The delivered entry
+    /// * `delivered_at` - When it was delivered
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Success or database error
+    async fn mark_delivered(&self, entry: &OutboxEntry, delivered_at: DateTime<Utc>) -> Result<()>;
+
+    /// Record a failed delivery attempt for an outbox entry
+    /// 
+    /// # Arguments
+    /// * `entry_id` - ID of the entry that failed
+    /// * `next_attempt_at` - When to try again, or `None` to move the entry to the dead letters
+    /// * `error` - Description of the failure
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Success or database error
+    async fn mark_failed(&self, entry_id: i64, next_attempt_at: Option<DateTime<Utc>>, error: &str) -> Result<()>;
+
+    /// Replace the stored saved searches with a new set, keeping creation times of unchanged names
+    /// 
+    /// # Arguments
+    /// * `searches` - The complete set of saved searches
+    /// 
+    /// # Returns
+    /// * `Result<()>` - Success or database error
+    async fn sync_saved_searches(&self, searches: &[SavedSearch]) -> Result<()>;
+
+    /// Get every saved search, ordered by name
+    /// 
+    /// # Returns
+    /// * `Result<Vec<SavedSearch>>` - Stored saved searches or database error
+    async fn get_saved_searches(&self) -> Result<Vec<SavedSearch>>;
+
+    /// Get stored listings matching a filter, most recently discovered first
+    /// 
+    /// # Arguments
+    /// * `filter` - Conditions the listings must meet
+    /// 
+    /// # Returns
+    /// * `Result<Vec<Jacket>>` - Matching listings or database error
+    async fn list_jackets(&self, filter: &ListingFilter) -> Result<Vec<Jacket>>;
+
+    /// Count the rows of every table
+    /// 
+    /// # Returns
+    /// * `Result<DatabaseStats>` - Row counts or database error
+    async fn stats(&self) -> Result<DatabaseStats>;
+
+    /// Record a completed check and how each scraper's search went
+    /// 
+    /// # Arguments
+    /// * `started_at` - When the check started
+    /// * `finished_at` - When the check finished saving its results
+    /// * `scrapers` - One entry per scraper
+    /// 
+    /// # Returns
+    /// * `Result<i64>` - ID of the recorded run or database error
+    async fn record_scrape_run(
+        &self,
+        started_at: DateTime<Utc>,
+        finished_at: DateTime<Utc>,
+        scrapers: &[ScraperRun],
+    ) -> Result<i64>;
+
+    /// Get the most recent checks with their per-scraper results, newest first
+    /// 
+    /// # Arguments
+    /// * `limit` - Maximum number of checks to return
+    /// 
+    /// # Returns
+    /// * `Result<Vec<ScrapeRun>>` - Recorded checks or database error
+    async fn recent_scrape_runs(&self, limit: u32) -> Result<Vec<ScrapeRun>>;
+
+    /// Mark every available listing of a website that was not seen since a point in time as missing
+    /// 
+    /// Sold out listings keep their status, so one that comes back is still back in stock.
+    /// 
+    /// # Arguments
+    /// * `source` - Name of the scraper whose search completed successfully
+    /// * `seen_since` - Start of the run; listings last seen before it are missing
+    /// 
+    /// # Returns
+    /// * `Result<u64>` - Number of listings newly marked missing or database error
+    async fn mark_missing(&self, source: &str, seen_since: DateTime<Utc>) -> Result<u64>;
+}